@@ -0,0 +1,102 @@
+use crate::divider::{find_combinations, CircuitParameters, Constraint};
+use crate::error::SolverError;
+use crate::ranged::Voltage;
+use crate::rc_param::Resistor;
+
+// Aim a little under full scale so the worst-case corner still fits.
+const FULL_SCALE_TARGET: f64 = 0.95;
+const FULL_SCALE_FLOOR: f64 = 0.9;
+// Codes are reported as u32.
+const MAX_ADC_BITS: u32 = 32;
+
+#[derive(Debug)]
+pub struct AdcDividerParameters {
+    pub divider: CircuitParameters,
+    pub full_scale_code: u32,
+    // Codes left between the worst-case maximum input and the top code.
+    pub code_margin: u32,
+}
+
+fn adc_code(v: f64, adc_vref: f64, adc_bits: u32) -> u32 {
+    let top = (1u64 << adc_bits) - 1;
+    ((v / adc_vref * (1u64 << adc_bits) as f64).floor().max(0.0) as u64).min(top) as u32
+}
+
+pub fn find_adc_divider(
+    adc_vref: f64,
+    adc_bits: u32,
+    sensor_max: Voltage,
+    resistors: Vec<Resistor>,
+) -> Result<Vec<AdcDividerParameters>, SolverError> {
+    if !(1..=MAX_ADC_BITS).contains(&adc_bits) {
+        return Err(SolverError::InvalidAdcBits(adc_bits));
+    }
+    let constraint = Constraint::builder()
+        .voltage(Voltage::new_by_values(
+            adc_vref * FULL_SCALE_TARGET,
+            adc_vref * FULL_SCALE_FLOOR,
+            adc_vref,
+        ))
+        .build()
+        .map_err(SolverError::InvalidConstraint)?;
+    let top = ((1u64 << adc_bits) - 1) as u32;
+    let mut results: Vec<AdcDividerParameters> =
+        find_combinations(constraint, sensor_max, resistors)?
+            .into_iter()
+            .filter(|params| params.vref.max() < adc_vref)
+            .map(|params| AdcDividerParameters {
+                full_scale_code: adc_code(params.vref.value, adc_vref, adc_bits),
+                code_margin: top - adc_code(params.vref.max(), adc_vref, adc_bits),
+                divider: params,
+            })
+            .collect();
+    if results.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    results.sort_by_key(|r| r.code_margin);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ConstraintError;
+    use crate::rc_param::{get_series_resistor_list, Series};
+
+    fn run(adc_vref: f64, adc_bits: u32) -> Result<Vec<AdcDividerParameters>, SolverError> {
+        find_adc_divider(
+            adc_vref,
+            adc_bits,
+            Voltage::new_by_allowance(12.0, 0.05),
+            get_series_resistor_list(Series::E24, 0.01),
+        )
+    }
+
+    #[test]
+    fn full_scale_fits_in_the_code_range() {
+        for bits in [1, 12, 32] {
+            let results = run(3.3, bits).unwrap();
+            let top = (1u64 << bits) - 1;
+            assert!(results
+                .iter()
+                .all(|r| u64::from(r.full_scale_code) <= top && u64::from(r.code_margin) <= top));
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_resolutions() {
+        assert_eq!(run(3.3, 0).err(), Some(SolverError::InvalidAdcBits(0)));
+        assert_eq!(run(3.3, 33).err(), Some(SolverError::InvalidAdcBits(33)));
+        assert_eq!(run(3.3, 64).err(), Some(SolverError::InvalidAdcBits(64)));
+    }
+
+    #[test]
+    fn reports_an_invalid_reference_as_a_constraint_error() {
+        assert_eq!(
+            run(f64::NAN, 12).err(),
+            Some(SolverError::InvalidConstraint(
+                ConstraintError::InvalidVoltageRange
+            ))
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use crate::divider::{Constraint, VrefSource};
+use crate::error::SolverError;
+use crate::ranged::Voltage;
+use crate::rc_param::{self, Capacitor, PassiveComponent};
+
+// Impedance is inversely proportional to capacitance, so the ratio is
+// flipped compared to a resistive divider: Vref = V * C1 / (C1 + C2).
+// Only `constraint.voltage` is read; the current, power, resistance and load
+// limits describe a resistive divider and do not apply here.
+#[derive(Debug)]
+pub struct CapacitiveDividerParameters {
+    pub c1: Capacitor,
+    pub c2: Capacitor,
+    pub vref: Voltage,
+    pub vref_error: f64,
+}
+
+pub fn find_capacitive_combinations(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    capacitors: Vec<Capacitor>,
+) -> Result<Vec<CapacitiveDividerParameters>, SolverError> {
+    let v_src = v_src.into();
+    if capacitors.is_empty() {
+        return Err(SolverError::EmptyComponentList);
+    }
+    if !capacitors
+        .iter()
+        .all(|c| c.min().is_finite() && c.max().is_finite())
+    {
+        return Err(SolverError::NonFiniteValue);
+    }
+
+    let capacitors = rc_param::without_zero_values(capacitors);
+    let mut results: Vec<CapacitiveDividerParameters> = capacitors
+        .iter()
+        .flat_map(|c1| capacitors.iter().map(move |c2| (c1, c2)))
+        .filter_map(|(c1, c2)| {
+            let v = c1.get_value() / (c1.get_value() + c2.get_value()) * v_src.value();
+            if v < constraint.voltage.min() || v > constraint.voltage.max() {
+                return None;
+            }
+            Some(CapacitiveDividerParameters {
+                c1: *c1,
+                c2: *c2,
+                vref: Voltage::new_by_values(
+                    v,
+                    c1.min() / (c1.min() + c2.max()) * v_src.min(),
+                    c1.max() / (c1.max() + c2.min()) * v_src.max(),
+                ),
+                vref_error: v - constraint.voltage.value,
+            })
+        })
+        .collect();
+    if results.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    results.sort_by(|a, b| {
+        a.vref_error.powi(2).total_cmp(&b.vref_error.powi(2)).then(
+            (a.c1.get_value() + a.c2.get_value()).total_cmp(&(b.c1.get_value() + b.c2.get_value())),
+        )
+    });
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constraint() -> Constraint {
+        Constraint::builder()
+            .voltage(Voltage::new_by_values(1.0, 0.9, 1.1))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn vref_is_c1_over_the_sum() {
+        let v_src = Voltage::new_by_allowance(3.0, 0.0);
+        let capacitors = vec![Capacitor::new(1e-9, 0.05), Capacitor::new(2e-9, 0.05)];
+        let best = find_capacitive_combinations(constraint(), v_src, capacitors).unwrap();
+        assert_eq!(best.len(), 1);
+        assert_eq!(best[0].c1.get_value(), 1e-9);
+        assert_eq!(best[0].c2.get_value(), 2e-9);
+        assert!((best[0].vref.value - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rejects_an_empty_list() {
+        let result =
+            find_capacitive_combinations(constraint(), Voltage::new_by_allowance(3.0, 0.0), vec![]);
+        assert_eq!(result.err(), Some(SolverError::EmptyComponentList));
+    }
+
+    #[test]
+    fn ignores_zero_value_parts() {
+        let v_src = Voltage::new_by_allowance(3.0, 0.0);
+        let wide = Constraint::builder()
+            .voltage(Voltage::new_by_values(1.0, 0.0, 3.0))
+            .build()
+            .unwrap();
+        let capacitors = vec![Capacitor::new(0.0, 0.05), Capacitor::new(1e-9, 0.05)];
+        let results = find_capacitive_combinations(wide, v_src, capacitors).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].vref.value.is_finite());
+    }
+}
@@ -0,0 +1,62 @@
+use crate::error::SolverError;
+use crate::ranged::{RangedType, RangedValue, Voltage};
+use crate::rc_param::{PassiveComponent, Resistor};
+
+// A series resistor feeding a clamp (Zener or shunt reference):
+// I = (Vsrc - Vclamp) / R must stay inside i_range over the supply band.
+#[derive(Debug)]
+pub struct SeriesResistorParameters {
+    pub r: Resistor,
+    pub current: RangedValue<f64>,
+    pub p_r: f64,
+}
+
+pub fn find_series_resistor(
+    v_src: Voltage,
+    v_clamp: Voltage,
+    i_range: RangedValue<f64>,
+    resistors: Vec<Resistor>,
+) -> Result<Vec<SeriesResistorParameters>, SolverError> {
+    if resistors.is_empty() {
+        return Err(SolverError::EmptyResistorList);
+    }
+    let inputs_finite = [v_src.value, v_src.min(), v_src.max()]
+        .into_iter()
+        .chain([v_clamp.value, v_clamp.min(), v_clamp.max()])
+        .chain(resistors.iter().flat_map(|r| [r.min(), r.max()]))
+        .all(f64::is_finite);
+    if !inputs_finite {
+        return Err(SolverError::NonFiniteValue);
+    }
+
+    let mut results: Vec<SeriesResistorParameters> = resistors
+        .iter()
+        .filter(|r| r.get_value() > 0.0)
+        .filter_map(|r| {
+            let current = RangedValue::new(
+                (v_src.value - v_clamp.value) / r.get_value(),
+                (v_src.min() - v_clamp.max()) / r.max(),
+                (v_src.max() - v_clamp.min()) / r.min(),
+            );
+            let p_r = (v_src.max() - v_clamp.min()).powi(2) / r.min();
+            let fits = current.get_min() >= i_range.get_min()
+                && current.get_max() <= i_range.get_max()
+                && p_r <= r.get_power_rating();
+            fits.then_some(SeriesResistorParameters {
+                r: *r,
+                current,
+                p_r,
+            })
+        })
+        .collect();
+    if results.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    let target = i_range.get_typical_value();
+    results.sort_by(|a, b| {
+        (a.current.value - target)
+            .abs()
+            .total_cmp(&(b.current.value - target).abs())
+    });
+    Ok(results)
+}
@@ -0,0 +1,184 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::rc_param::{Series, Tolerance};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    pub vcc: f64,
+    pub vcc_tolerance: f64,
+    pub vref: f64,
+    pub vref_min: f64,
+    pub vref_max: f64,
+    pub max_current: f64,
+    pub tolerance: f64,
+    pub series: Series,
+    pub min_total_resistance: f64,
+    pub max_total_resistance: f64,
+    pub count: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            vcc: 5.0,
+            vcc_tolerance: 0.25,
+            vref: 2.0,
+            vref_min: 0.5,
+            vref_max: 4.0,
+            max_current: 5e-4,
+            tolerance: 0.01,
+            series: Series::E24,
+            min_total_resistance: 10e3,
+            max_total_resistance: 120e3,
+            count: 10,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Syntax(usize),
+    UnknownKey(String),
+    InvalidValue(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "cannot read config: {e}"),
+            ConfigError::Syntax(line) => write!(f, "config syntax error on line {line}"),
+            ConfigError::UnknownKey(key) => write!(f, "unknown config key `{key}`"),
+            ConfigError::InvalidValue(key) => write!(f, "invalid value for config key `{key}`"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+// Reads a plain number; TOML writes `5` and `5.0` as different types.
+fn number(key: &str, value: &toml::Value) -> Result<f64, ConfigError> {
+    match value {
+        toml::Value::Float(x) => Ok(*x),
+        toml::Value::Integer(n) => Ok(*n as f64),
+        _ => Err(ConfigError::InvalidValue(key.to_string())),
+    }
+}
+
+impl Config {
+    // Keys are matched by hand rather than through a serde derive so that
+    // serde stays an optional dependency of the library.
+    pub fn from_toml(input: &str) -> Result<Config, ConfigError> {
+        let table: toml::Table = input.parse().map_err(|e: toml::de::Error| {
+            let offset = e.span().map_or(0, |span| span.start);
+            ConfigError::Syntax(input[..offset].matches('\n').count() + 1)
+        })?;
+        let mut config = Config::default();
+        for (key, value) in &table {
+            let invalid = || ConfigError::InvalidValue(key.clone());
+            match key.as_str() {
+                "series" => {
+                    config.series = value
+                        .as_str()
+                        .and_then(|name| name.parse().ok())
+                        .ok_or_else(invalid)?
+                }
+                "count" => {
+                    config.count = value
+                        .as_integer()
+                        .and_then(|n| usize::try_from(n).ok())
+                        .filter(|&n| n > 0)
+                        .ok_or_else(invalid)?
+                }
+                "vcc" => {
+                    config.vcc = Some(number(key, value)?)
+                        .filter(|v| v.is_finite() && *v > 0.0)
+                        .ok_or_else(invalid)?
+                }
+                "vcc_tolerance" => {
+                    config.vcc_tolerance = Tolerance::try_fraction(number(key, value)?)
+                        .map_err(|_| invalid())?
+                        .as_fraction()
+                }
+                "vref" => config.vref = number(key, value)?,
+                "vref_min" => config.vref_min = number(key, value)?,
+                "vref_max" => config.vref_max = number(key, value)?,
+                "max_current" => config.max_current = number(key, value)?,
+                "tolerance" => {
+                    config.tolerance = Tolerance::try_fraction(number(key, value)?)
+                        .map_err(|_| invalid())?
+                        .as_fraction()
+                }
+                "min_total_resistance" => config.min_total_resistance = number(key, value)?,
+                "max_total_resistance" => config.max_total_resistance = number(key, value)?,
+                _ => return Err(ConfigError::UnknownKey(key.clone())),
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Config, ConfigError> {
+        Config::from_toml(&std::fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_integers_floats_and_strings() {
+        let config = Config::from_toml(
+            "# divider for the ADC\nvcc = 12\nvref = 3.3\nmax_total_resistance = 220_000\nseries = \"e96\"\ncount = 3\n",
+        )
+        .unwrap();
+        assert_eq!(config.vcc, 12.0);
+        assert_eq!(config.vref, 3.3);
+        assert_eq!(config.max_total_resistance, 220e3);
+        assert_eq!(config.series, Series::E96);
+        assert_eq!(config.count, 3);
+    }
+
+    #[test]
+    fn reports_bad_input() {
+        assert!(matches!(
+            Config::from_toml("vcc = 5\nvref 2.5\n"),
+            Err(ConfigError::Syntax(2))
+        ));
+        assert!(matches!(
+            Config::from_toml("max_curent = 1e-3"),
+            Err(ConfigError::UnknownKey(key)) if key == "max_curent"
+        ));
+        assert!(matches!(
+            Config::from_toml("count = 2.5"),
+            Err(ConfigError::InvalidValue(key)) if key == "count"
+        ));
+        assert!(matches!(
+            Config::from_toml("count = 0"),
+            Err(ConfigError::InvalidValue(key)) if key == "count"
+        ));
+        for input in [
+            "vcc = 0",
+            "vcc = -5",
+            "vcc_tolerance = -3",
+            "vcc_tolerance = 1",
+        ] {
+            let key = input.split(' ').next().unwrap();
+            assert!(matches!(
+                Config::from_toml(input),
+                Err(ConfigError::InvalidValue(k)) if k == key
+            ));
+        }
+        assert!(matches!(
+            Config::from_toml("tolerance = 1.5"),
+            Err(ConfigError::InvalidValue(key)) if key == "tolerance"
+        ));
+    }
+}
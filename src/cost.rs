@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use crate::divider::CircuitParameters;
+use crate::rc_param::{PassiveComponent, Resistor};
+
+// Keyed by value in µΩ and tolerance in ppm, the same quantization Resistor
+// equality uses, so list values with rounding noise still find their price.
+#[derive(Clone, Debug, Default)]
+pub struct PriceTable {
+    prices: HashMap<(i64, i64), f64>,
+}
+
+fn price_key(value: f64, tolerance: f64) -> (i64, i64) {
+    (
+        (value * 1e6).round() as i64,
+        (tolerance * 1e6).round() as i64,
+    )
+}
+
+impl PriceTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, value: f64, tolerance: f64, unit_price: f64) {
+        self.prices.insert(price_key(value, tolerance), unit_price);
+    }
+
+    pub fn price(&self, resistor: &Resistor) -> Option<f64> {
+        self.prices
+            .get(&price_key(resistor.get_value(), resistor.get_tolerance()))
+            .copied()
+    }
+}
+
+#[derive(Debug)]
+pub struct CostedParameters {
+    pub params: CircuitParameters,
+    // Every physical part of both arms with its unit price.
+    pub part_costs: Vec<(Resistor, f64)>,
+    pub total_cost: f64,
+}
+
+// Cheapest first, then by Vref error. Combinations using a part missing from
+// the table are dropped rather than treated as free.
+pub fn rank_by_cost(results: Vec<CircuitParameters>, prices: &PriceTable) -> Vec<CostedParameters> {
+    let mut ranked = results
+        .into_iter()
+        .filter_map(|params| {
+            let part_costs = params
+                .r1_network
+                .parts()
+                .into_iter()
+                .chain(params.r2_network.parts())
+                .map(|r| prices.price(&r).map(|p| (r, p)))
+                .collect::<Option<Vec<_>>>()?;
+            let total_cost = part_costs.iter().map(|(_, p)| p).sum();
+            Some(CostedParameters {
+                params,
+                part_costs,
+                total_cost,
+            })
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by(|a, b| {
+        a.total_cost
+            .total_cmp(&b.total_cost)
+            .then_with(|| a.params.cmp(&b.params))
+    });
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::divider::{find_combinations, Constraint};
+    use crate::ranged::Voltage;
+    use crate::rc_param::small_test_resistors;
+
+    #[test]
+    fn ranks_cheapest_first_and_drops_unpriced_parts() {
+        // From 3 V, 1k/1k, 2k/2k, 3k/3k, 3k/2k and 2k/3k land in 1.1..1.9 V.
+        let constraint = Constraint::builder()
+            .voltage(Voltage::new_by_values(1.5, 1.1, 1.9))
+            .build()
+            .unwrap();
+        let v_src = Voltage::new_by_allowance(3.0, 0.0);
+        let results = find_combinations(constraint, v_src, small_test_resistors()).unwrap();
+        assert_eq!(results.len(), 5);
+
+        // No price for 3k, so only the 1k and 2k pairs are left.
+        let mut prices = PriceTable::new();
+        prices.insert(1e3, 0.01, 0.02);
+        prices.insert(2e3, 0.01, 0.01);
+        let ranked = rank_by_cost(results, &prices);
+        let summary = ranked
+            .iter()
+            .map(|c| {
+                (
+                    c.params.r1.get_value(),
+                    c.params.r2.get_value(),
+                    c.total_cost,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(summary, [(2e3, 2e3, 0.02), (1e3, 1e3, 0.04)]);
+        assert!(ranked.iter().all(|c| c.part_costs.len() == 2));
+    }
+}
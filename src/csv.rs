@@ -0,0 +1,48 @@
+use std::io::{self, Write};
+
+use crate::divider::CircuitParameters;
+use crate::format::engineering_notation;
+use crate::rc_param::PassiveComponent;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CsvOptions {
+    pub engineering_notation: bool,
+    pub output_gain: Option<f64>,
+}
+
+pub fn write_csv<W: Write>(results: &[CircuitParameters], w: W) -> io::Result<()> {
+    write_csv_with(results, w, &CsvOptions::default())
+}
+
+pub fn write_csv_with<W: Write>(
+    results: &[CircuitParameters],
+    mut w: W,
+    options: &CsvOptions,
+) -> io::Result<()> {
+    let cell = |value: f64, unit: &str| {
+        if options.engineering_notation {
+            engineering_notation(value, unit)
+        } else {
+            value.to_string()
+        }
+    };
+    writeln!(w, "r1,r2,vref,vref_min,vref_max,vref_error,iout")?;
+    for params in results {
+        let iout = options
+            .output_gain
+            .map(|gain| cell(gain * params.vref.value, "A"))
+            .unwrap_or_default();
+        writeln!(
+            w,
+            "{},{},{},{},{},{},{}",
+            cell(params.r1.get_value(), "Ω"),
+            cell(params.r2.get_value(), "Ω"),
+            cell(params.vref.value, "V"),
+            cell(params.vref.min(), "V"),
+            cell(params.vref.max(), "V"),
+            cell(params.vref_error.value(), "V"),
+            iout,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,1298 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use crate::error::{ConstraintError, SolverError};
+use crate::format::prefixed_for_resistance_sig;
+use crate::ranged::{RangedType, RangedValue, Voltage};
+use crate::rc_filter::rc_cutoff;
+use crate::rc_param::{
+    self, Capacitor, Network, PassiveComponent, Potentiometer, Resistor, Series,
+};
+use crate::units::{Amps, Ohms, Volts, Watts};
+
+#[derive(Copy, Clone)]
+pub struct Constraint {
+    pub voltage: Voltage,
+    pub max_current: Amps,
+    pub max_resistor_power: Option<Watts>,
+    pub load: Option<Resistor>,
+    pub min_total_resistance: Ohms,
+    pub max_total_resistance: Ohms,
+    pub max_thevenin_resistance: Option<Ohms>,
+    pub max_quiescent_power: Option<Watts>,
+}
+
+impl Constraint {
+    pub fn builder() -> ConstraintBuilder {
+        ConstraintBuilder::default()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ConstraintBuilder {
+    voltage: Option<Voltage>,
+    max_current: Amps,
+    max_resistor_power: Option<Watts>,
+    load: Option<Resistor>,
+    min_total_resistance: Ohms,
+    max_total_resistance: Ohms,
+    max_thevenin_resistance: Option<Ohms>,
+    max_quiescent_power: Option<Watts>,
+}
+
+impl Default for ConstraintBuilder {
+    fn default() -> Self {
+        Self {
+            voltage: None,
+            max_current: Amps(f64::INFINITY),
+            max_resistor_power: None,
+            load: None,
+            min_total_resistance: Ohms(0.0),
+            max_total_resistance: Ohms(f64::INFINITY),
+            max_thevenin_resistance: None,
+            max_quiescent_power: None,
+        }
+    }
+}
+
+impl ConstraintBuilder {
+    pub fn voltage(mut self, voltage: Voltage) -> Self {
+        self.voltage = Some(voltage);
+        self
+    }
+
+    pub fn max_current(mut self, max_current: Amps) -> Self {
+        self.max_current = max_current;
+        self
+    }
+
+    pub fn max_resistor_power(mut self, max_resistor_power: Watts) -> Self {
+        self.max_resistor_power = Some(max_resistor_power);
+        self
+    }
+
+    pub fn load(mut self, load: Resistor) -> Self {
+        self.load = Some(load);
+        self
+    }
+
+    pub fn min_total_resistance(mut self, min_total_resistance: Ohms) -> Self {
+        self.min_total_resistance = min_total_resistance;
+        self
+    }
+
+    pub fn max_total_resistance(mut self, max_total_resistance: Ohms) -> Self {
+        self.max_total_resistance = max_total_resistance;
+        self
+    }
+
+    pub fn max_thevenin_resistance(mut self, max_thevenin_resistance: Ohms) -> Self {
+        self.max_thevenin_resistance = Some(max_thevenin_resistance);
+        self
+    }
+
+    pub fn max_quiescent_power(mut self, max_quiescent_power: Watts) -> Self {
+        self.max_quiescent_power = Some(max_quiescent_power);
+        self
+    }
+
+    pub fn build(self) -> Result<Constraint, ConstraintError> {
+        let voltage = self.voltage.ok_or(ConstraintError::MissingVoltage)?;
+        if !(voltage.min() <= voltage.value && voltage.value <= voltage.max()) {
+            return Err(ConstraintError::InvalidVoltageRange);
+        }
+        let max_current = self.max_current.value();
+        if max_current.is_nan() || max_current <= 0.0 {
+            return Err(ConstraintError::InvalidCurrentLimit);
+        }
+        let (min_total, max_total) = (
+            self.min_total_resistance.value(),
+            self.max_total_resistance.value(),
+        );
+        if !(0.0 <= min_total && min_total <= max_total) {
+            return Err(ConstraintError::InvalidResistanceWindow);
+        }
+        Ok(Constraint {
+            voltage,
+            max_current: self.max_current,
+            max_resistor_power: self.max_resistor_power,
+            load: self.load,
+            min_total_resistance: self.min_total_resistance,
+            max_total_resistance: self.max_total_resistance,
+            max_thevenin_resistance: self.max_thevenin_resistance,
+            max_quiescent_power: self.max_quiescent_power,
+        })
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug)]
+pub struct CircuitParameters {
+    pub r1: Resistor,
+    pub r2: Resistor,
+    pub r1_network: Network,
+    pub r2_network: Network,
+    // Resistive load across R2 that the vref range already includes.
+    pub load: Option<Resistor>,
+    pub vref: Voltage,
+    pub vref_error: Volts,
+    pub p_r1: Watts,
+    pub p_r2: Watts,
+    pub p_quiescent: Watts,
+    pub r_thevenin: RangedValue<Ohms>,
+    pub filter_poles: Vec<FilterPole>,
+    pub(crate) ratio: RangedValue<f64>,
+}
+
+impl CircuitParameters {
+    // Half-width of the Vref range relative to the typical value.
+    pub fn accuracy(&self) -> f64 {
+        (self.vref.max() - self.vref.min()) / (2.0 * self.vref.value)
+    }
+
+    // R2 / (R1 + R2) including any load, independent of the supply.
+    pub fn ratio(&self) -> RangedValue<f64> {
+        self.ratio
+    }
+
+    pub fn vref_error_percent(&self, target: &Voltage) -> f64 {
+        self.vref_error.value() / target.value * 100.0
+    }
+
+    // Same significand in different decades, e.g. 4.7 kΩ and 47 kΩ, for
+    // better tempco tracking.
+    pub fn has_matched_significand(&self) -> bool {
+        let a = significand(self.r1.get_value());
+        let b = significand(self.r2.get_value());
+        (a - b).abs() <= 1e-6 * a
+    }
+}
+
+// Ordered by vref_error², then total resistance, the default ranking.
+impl PartialEq for CircuitParameters {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CircuitParameters {}
+
+impl PartialOrd for CircuitParameters {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CircuitParameters {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_by_error(self, other)
+    }
+}
+
+impl fmt::Display for CircuitParameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (r1, r1_prefix) = prefixed_for_resistance_sig(self.r1.get_value(), 3);
+        writeln!(f, "R1: {r1} {r1_prefix}Ω")?;
+        let (r2, r2_prefix) = prefixed_for_resistance_sig(self.r2.get_value(), 3);
+        writeln!(f, "R2: {r2} {r2_prefix}Ω")?;
+        writeln!(f, "Vref: {}", self.vref.value)?;
+        writeln!(f, "Vref Range: {}, {}", self.vref.min(), self.vref.max())?;
+        write!(f, "Vref Error: {}", self.vref_error.value())
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug)]
+pub struct FilterPole {
+    pub capacitor: Capacitor,
+    pub cutoff: RangedValue<f64>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RankBy {
+    #[default]
+    VrefError,
+    TotalResistance,
+    // Highest Vref first, which is highest current for any positive sense gain.
+    OutputCurrent,
+    TheveninResistance,
+    // vref_error² scaled by under_weight below the target and over_weight above.
+    WeightedError {
+        under_weight: f64,
+        over_weight: f64,
+    },
+}
+
+impl RankBy {
+    pub fn compare(&self, a: &CircuitParameters, b: &CircuitParameters) -> Ordering {
+        let primary = match self {
+            RankBy::VrefError => Ordering::Equal,
+            RankBy::TotalResistance => (a.r1.get_value() + a.r2.get_value())
+                .total_cmp(&(b.r1.get_value() + b.r2.get_value())),
+            RankBy::OutputCurrent => b.vref.value.total_cmp(&a.vref.value),
+            RankBy::TheveninResistance => a
+                .r_thevenin
+                .get_typical_value()
+                .value()
+                .total_cmp(&b.r_thevenin.get_typical_value().value()),
+            RankBy::WeightedError {
+                under_weight,
+                over_weight,
+            } => {
+                let weighted =
+                    |e: f64| e.powi(2) * if e < 0.0 { *under_weight } else { *over_weight };
+                weighted(a.vref_error.value()).total_cmp(&weighted(b.vref_error.value()))
+            }
+        };
+        primary.then_with(|| compare_by_error(a, b))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchOptions {
+    pub max_components_per_arm: usize,
+    pub capacitors: Vec<Capacitor>,
+    pub rank_by: RankBy,
+    pub prefer_matched_significand: bool,
+    // Every physical part, including each part of a series/parallel network,
+    // must come from this series. The combined value of a network need not.
+    pub parts_series: Option<Series>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            max_components_per_arm: 1,
+            capacitors: Vec::new(),
+            rank_by: RankBy::default(),
+            prefer_matched_significand: false,
+            parts_series: None,
+        }
+    }
+}
+
+fn significand(value: f64) -> f64 {
+    value / 10f64.powi(value.log10().floor() as i32)
+}
+
+fn parallel(a: f64, b: f64) -> f64 {
+    a * b / (a + b)
+}
+
+fn lower_arm(r2: &Resistor, load: Option<Resistor>) -> (f64, f64, f64) {
+    match load {
+        Some(load) => (
+            parallel(r2.get_value(), load.get_value()),
+            parallel(r2.min(), load.min()),
+            parallel(r2.max(), load.max()),
+        ),
+        None => (r2.get_value(), r2.min(), r2.max()),
+    }
+}
+
+// A capacitor across R2 sees R1 ∥ R2 (∥ load), so that is what sets the pole.
+fn filter_poles(
+    params: &CircuitParameters,
+    load: Option<Resistor>,
+    capacitors: &[Capacitor],
+) -> Vec<FilterPole> {
+    let lower = match load {
+        Some(load) => params.r2.parallel(&load),
+        None => params.r2,
+    };
+    let r_thevenin = params.r1.parallel(&lower);
+    capacitors
+        .iter()
+        .map(|c| FilterPole {
+            capacitor: *c,
+            cutoff: rc_cutoff(&r_thevenin, c),
+        })
+        .collect()
+}
+
+fn divider_parameters(
+    r1_network: &Network,
+    r2_network: &Network,
+    load: Option<Resistor>,
+    v_src: &VrefSource,
+    target: f64,
+) -> CircuitParameters {
+    let r1 = r1_network.equivalent();
+    let (r1_v, r1_min, r1_max) = (r1.get_value(), r1.min(), r1.max());
+    let r2 = r2_network.equivalent();
+    let (r2_v, r2_min, r2_max) = lower_arm(&r2, load);
+    let r = r2_v / (r1_v + r2_v);
+    let vref = {
+        let v_max = (r2_max / (r1_min + r2_max)) * v_src.max();
+        let v_min = (r2_min / (r1_max + r2_min)) * v_src.min();
+        Voltage::new_by_values(r * v_src.value(), v_min, v_max)
+    };
+    let i_max = v_src.max() / (r1_min + r2_min);
+    CircuitParameters {
+        r1,
+        r2,
+        r1_network: *r1_network,
+        r2_network: *r2_network,
+        load,
+        vref,
+        vref_error: Volts(vref.value - target),
+        p_r1: Watts(i_max.powi(2) * r1_max),
+        p_r2: Watts(vref.max().powi(2) / r2.min()),
+        p_quiescent: Watts(v_src.max() * i_max),
+        r_thevenin: RangedValue::new(
+            Ohms(parallel(r1_v, r2_v)),
+            Ohms(parallel(r1_min, r2_min)),
+            Ohms(parallel(r1_max, r2_max)),
+        ),
+        filter_poles: Vec::new(),
+        ratio: RangedValue::new(r, r2_min / (r1_max + r2_min), r2_max / (r1_min + r2_max)),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    pub examined: usize,
+    pub passed: usize,
+    pub rejected_total_resistance: usize,
+    pub rejected_current: usize,
+    pub rejected_vref_low: usize,
+    pub rejected_vref_high: usize,
+    pub rejected_power: usize,
+    pub rejected_thevenin: usize,
+    pub rejected_quiescent_power: usize,
+}
+
+impl SearchStats {
+    fn record(&mut self, result: &Result<CircuitParameters, Rejection>) {
+        self.examined += 1;
+        let counter = match result {
+            Ok(_) => &mut self.passed,
+            Err(Rejection::TotalResistance) => &mut self.rejected_total_resistance,
+            Err(Rejection::Current) => &mut self.rejected_current,
+            Err(Rejection::VrefLow) => &mut self.rejected_vref_low,
+            Err(Rejection::VrefHigh) => &mut self.rejected_vref_high,
+            Err(Rejection::Power) => &mut self.rejected_power,
+            Err(Rejection::Thevenin) => &mut self.rejected_thevenin,
+            Err(Rejection::QuiescentPower) => &mut self.rejected_quiescent_power,
+        };
+        *counter += 1;
+    }
+}
+
+// The first filter a pair fails, in the order they are applied.
+enum Rejection {
+    TotalResistance,
+    Current,
+    VrefLow,
+    VrefHigh,
+    Power,
+    Thevenin,
+    QuiescentPower,
+}
+
+fn classify(
+    r1_network: &Network,
+    r2_network: &Network,
+    constraint: &Constraint,
+    v_src: &VrefSource,
+) -> Result<CircuitParameters, Rejection> {
+    let r1 = r1_network.equivalent();
+    let (r1_v, r1_min, r1_max) = (r1.get_value(), r1.min(), r1.max());
+    let r2 = r2_network.equivalent();
+    if r1_min + r2.min() < constraint.min_total_resistance.value()
+        || r1_max + r2.max() > constraint.max_total_resistance.value()
+    {
+        return Err(Rejection::TotalResistance);
+    }
+
+    let (r2_v, r2_min, _) = lower_arm(&r2, constraint.load);
+    let max_curr = v_src.max() / (r1_min + r2_min);
+    let vref = r2_v / (r1_v + r2_v) * v_src.value();
+    if max_curr.is_nan() || max_curr > constraint.max_current.value() {
+        return Err(Rejection::Current);
+    }
+    if vref.is_nan() || vref < constraint.voltage.min() {
+        return Err(Rejection::VrefLow);
+    }
+    if vref > constraint.voltage.max() {
+        return Err(Rejection::VrefHigh);
+    }
+
+    let params = divider_parameters(
+        r1_network,
+        r2_network,
+        constraint.load,
+        v_src,
+        constraint.voltage.value,
+    );
+    let within_rating = params.p_r1.value() <= params.r1.get_power_rating()
+        && params.p_r2.value() <= params.r2.get_power_rating();
+    let within_power = match constraint.max_resistor_power {
+        Some(p_max) => params.p_r1 <= p_max && params.p_r2 <= p_max,
+        None => true,
+    };
+    if !(within_rating && within_power) {
+        return Err(Rejection::Power);
+    }
+    if let Some(r_max) = constraint.max_thevenin_resistance {
+        if params.r_thevenin.get_max() > r_max {
+            return Err(Rejection::Thevenin);
+        }
+    }
+    if let Some(p_max) = constraint.max_quiescent_power {
+        if params.p_quiescent > p_max {
+            return Err(Rejection::QuiescentPower);
+        }
+    }
+    Ok(params)
+}
+
+fn combination(
+    r1_network: &Network,
+    r2_network: &Network,
+    constraint: &Constraint,
+    v_src: &VrefSource,
+) -> Option<CircuitParameters> {
+    classify(r1_network, r2_network, constraint, v_src).ok()
+}
+
+fn combinations_for_r1<'a>(
+    r1_network: &'a Network,
+    r2_networks: &'a [Network],
+    constraint: &'a Constraint,
+    v_src: &'a VrefSource,
+) -> impl Iterator<Item = CircuitParameters> + 'a {
+    r2_networks
+        .iter()
+        .filter_map(move |r2_network| combination(r1_network, r2_network, constraint, v_src))
+}
+
+#[cfg(not(feature = "rayon"))]
+fn collect_combinations(
+    r1_networks: &[Network],
+    r2_networks: &[Network],
+    constraint: &Constraint,
+    v_src: &VrefSource,
+) -> Vec<CircuitParameters> {
+    r1_networks
+        .iter()
+        .flat_map(|r1_network| combinations_for_r1(r1_network, r2_networks, constraint, v_src))
+        .collect::<Vec<_>>()
+}
+
+#[cfg(feature = "rayon")]
+fn collect_combinations(
+    r1_networks: &[Network],
+    r2_networks: &[Network],
+    constraint: &Constraint,
+    v_src: &VrefSource,
+) -> Vec<CircuitParameters> {
+    use rayon::prelude::*;
+
+    r1_networks
+        .par_iter()
+        .flat_map_iter(|r1_network| combinations_for_r1(r1_network, r2_networks, constraint, v_src))
+        .collect::<Vec<_>>()
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum VrefSource {
+    Vcc(Voltage),
+    Regulator(Voltage),
+}
+
+impl VrefSource {
+    pub fn voltage(&self) -> Voltage {
+        match self {
+            VrefSource::Vcc(v) | VrefSource::Regulator(v) => *v,
+        }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.voltage().value
+    }
+
+    pub fn min(&self) -> f64 {
+        self.voltage().min()
+    }
+
+    pub fn max(&self) -> f64 {
+        self.voltage().max()
+    }
+}
+
+impl From<Voltage> for VrefSource {
+    fn from(v: Voltage) -> Self {
+        VrefSource::Vcc(v)
+    }
+}
+
+fn compare_by_error(a: &CircuitParameters, b: &CircuitParameters) -> Ordering {
+    let x = a.vref_error.value().powi(2);
+    let y = b.vref_error.value().powi(2);
+    x.total_cmp(&y).then_with(|| {
+        (a.r1.get_value() + a.r2.get_value()).total_cmp(&(b.r1.get_value() + b.r2.get_value()))
+    })
+}
+
+fn has_finite_vref(params: &CircuitParameters) -> bool {
+    params.vref.value.is_finite() && params.vref.min().is_finite() && params.vref.max().is_finite()
+}
+
+fn validate_inputs(
+    constraint: &Constraint,
+    v_src: &VrefSource,
+    resistors: &[Resistor],
+) -> Result<(), SolverError> {
+    if resistors.is_empty() {
+        return Err(SolverError::EmptyResistorList);
+    }
+    let voltages_finite = [
+        v_src.value(),
+        v_src.min(),
+        v_src.max(),
+        constraint.voltage.value,
+        constraint.voltage.min(),
+        constraint.voltage.max(),
+    ]
+    .iter()
+    .all(|v| v.is_finite());
+    let resistors_finite = resistors
+        .iter()
+        .chain(constraint.load.iter())
+        .all(|r| r.min().is_finite() && r.max().is_finite());
+    if !voltages_finite || !resistors_finite || constraint.max_current.value().is_nan() {
+        return Err(SolverError::NonFiniteValue);
+    }
+    Ok(())
+}
+
+pub fn find_combinations(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    find_combinations_with(constraint, v_src, resistors, &SearchOptions::default())
+}
+
+// Unsorted and lazy, so callers can stop early or apply their own filters.
+pub fn combinations_iter(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+) -> Result<impl Iterator<Item = CircuitParameters>, SolverError> {
+    let v_src = v_src.into();
+    validate_inputs(&constraint, &v_src, &resistors)?;
+
+    let networks = rc_param::expand_networks(&rc_param::without_zero_values(resistors), 1);
+    let n = networks.len();
+    Ok((0..n * n)
+        .filter_map(move |i| combination(&networks[i / n], &networks[i % n], &constraint, &v_src)))
+}
+
+// Same as find_combinations, but counts which filter rejected each pair.
+// The stats are filled in even when no combination passes.
+pub fn find_combinations_with_stats(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+    stats: &mut SearchStats,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    let v_src = v_src.into();
+    validate_inputs(&constraint, &v_src, &resistors)?;
+
+    let networks = rc_param::expand_networks(&rc_param::without_zero_values(resistors), 1);
+    let mut combinations = Vec::new();
+    for r1_network in &networks {
+        for r2_network in &networks {
+            let result = classify(r1_network, r2_network, &constraint, &v_src);
+            stats.record(&result);
+            combinations.extend(result.ok());
+        }
+    }
+    if combinations.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    if !combinations.iter().all(has_finite_vref) {
+        return Err(SolverError::NonFiniteValue);
+    }
+    combinations.sort_unstable();
+    Ok(combinations)
+}
+
+// Every combination whose typical Vref is within ±max_error_fraction of the
+// target, sorted by error. The constraint's own window still applies.
+pub fn combinations_within(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+    max_error_fraction: f64,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    if !max_error_fraction.is_finite() {
+        return Err(SolverError::NonFiniteValue);
+    }
+    let band = max_error_fraction.abs() * constraint.voltage.value;
+    let mut combinations = combinations_iter(constraint, v_src, resistors)?
+        .filter(|params| params.vref_error.value().abs() <= band)
+        .collect::<Vec<_>>();
+    if combinations.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    if !combinations.iter().all(has_finite_vref) {
+        return Err(SolverError::NonFiniteValue);
+    }
+    combinations.sort_unstable();
+    Ok(combinations)
+}
+
+// Nearest result below the target and nearest at or above it.
+pub fn bracketing_dividers(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+) -> Result<(Option<CircuitParameters>, Option<CircuitParameters>), SolverError> {
+    let combinations = match find_combinations(constraint, v_src, resistors) {
+        Ok(combinations) => combinations,
+        Err(SolverError::NoCombinationFound) => return Ok((None, None)),
+        Err(e) => return Err(e),
+    };
+    let (mut below, mut above) = (None, None);
+    for params in combinations {
+        match (params.vref_error.value() < 0.0, &below, &above) {
+            (true, None, _) => below = Some(params),
+            (false, _, None) => above = Some(params),
+            _ => {}
+        }
+        if below.is_some() && above.is_some() {
+            break;
+        }
+    }
+    Ok((below, above))
+}
+
+// R1 and R2 drawn from separate pools, e.g. a 5% part over a 0.1% part.
+pub fn find_combinations_asym(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    r1_pool: Vec<Resistor>,
+    r2_pool: Vec<Resistor>,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    let v_src = v_src.into();
+    validate_inputs(&constraint, &v_src, &r1_pool)?;
+    validate_inputs(&constraint, &v_src, &r2_pool)?;
+
+    let options = SearchOptions::default();
+    let r1_networks = rc_param::expand_networks(&rc_param::without_zero_values(r1_pool), 1);
+    let r2_networks = rc_param::expand_networks(&rc_param::without_zero_values(r2_pool), 1);
+    search_networks(
+        &constraint,
+        &v_src,
+        &r1_networks,
+        &r2_networks,
+        &options,
+        |a, b| options.rank_by.compare(a, b),
+    )
+}
+
+// Combinations whose R1 ∥ R2 matches the reference within a relative epsilon,
+// so a second leg loads the source the same way. The reference pair itself
+// is left out; the ratio may differ.
+pub fn find_matched_thevenin(
+    reference: &CircuitParameters,
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+    epsilon: f64,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    let target = reference.r_thevenin.get_typical_value().value();
+    let mismatch =
+        |params: &CircuitParameters| (params.r_thevenin.get_typical_value().value() - target).abs();
+    let mut matched: Vec<CircuitParameters> = find_combinations(constraint, v_src, resistors)?
+        .into_iter()
+        .filter(|params| {
+            params.r1.get_value() != reference.r1.get_value()
+                || params.r2.get_value() != reference.r2.get_value()
+        })
+        .filter(|params| mismatch(params) <= epsilon * target)
+        .collect();
+    if matched.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    matched.sort_by(|a, b| mismatch(a).total_cmp(&mismatch(b)).then_with(|| a.cmp(b)));
+    Ok(matched)
+}
+
+// One result per target, in order. The network list is built once and shared.
+pub fn find_combinations_multi(
+    targets: &[Constraint],
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+) -> Vec<Result<Vec<CircuitParameters>, SolverError>> {
+    let v_src = v_src.into();
+    let options = SearchOptions::default();
+    let networks = rc_param::expand_networks(&rc_param::without_zero_values(resistors.clone()), 1);
+    targets
+        .iter()
+        .map(|constraint| {
+            validate_inputs(constraint, &v_src, &resistors)?;
+            search_networks(
+                constraint,
+                &v_src,
+                &networks,
+                &networks,
+                &options,
+                |a, b| options.rank_by.compare(a, b),
+            )
+        })
+        .collect()
+}
+
+pub fn find_combinations_with(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+    options: &SearchOptions,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    search(constraint, v_src.into(), resistors, options, |a, b| {
+        let matched = if options.prefer_matched_significand {
+            b.has_matched_significand()
+                .cmp(&a.has_matched_significand())
+        } else {
+            Ordering::Equal
+        };
+        matched.then_with(|| options.rank_by.compare(a, b))
+    })
+}
+
+pub fn find_combinations_by(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+    cmp: impl Fn(&CircuitParameters, &CircuitParameters) -> Ordering,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    search(
+        constraint,
+        v_src.into(),
+        resistors,
+        &SearchOptions::default(),
+        cmp,
+    )
+}
+
+fn search(
+    constraint: Constraint,
+    v_src: VrefSource,
+    resistors: Vec<Resistor>,
+    options: &SearchOptions,
+    cmp: impl Fn(&CircuitParameters, &CircuitParameters) -> Ordering,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    validate_inputs(&constraint, &v_src, &resistors)?;
+
+    let mut resistors = rc_param::without_zero_values(resistors);
+    if let Some(series) = options.parts_series {
+        resistors.retain(|r| series.contains(r.get_value()));
+    }
+    let networks = rc_param::expand_networks(&resistors, options.max_components_per_arm);
+    search_networks(&constraint, &v_src, &networks, &networks, options, cmp)
+}
+
+fn search_networks(
+    constraint: &Constraint,
+    v_src: &VrefSource,
+    r1_networks: &[Network],
+    r2_networks: &[Network],
+    options: &SearchOptions,
+    cmp: impl Fn(&CircuitParameters, &CircuitParameters) -> Ordering,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    log::trace!(
+        "searching {} R1 and {} R2 networks",
+        r1_networks.len(),
+        r2_networks.len()
+    );
+    let t = std::time::Instant::now();
+    let mut combinations = collect_combinations(r1_networks, r2_networks, constraint, v_src);
+    log::debug!(
+        "found {} combinations in {:?}",
+        combinations.len(),
+        t.elapsed()
+    );
+    if combinations.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    if !combinations.iter().all(has_finite_vref) {
+        return Err(SolverError::NonFiniteValue);
+    }
+    combinations.sort_unstable_by(cmp);
+    if !options.capacitors.is_empty() {
+        for params in combinations.iter_mut() {
+            params.filter_poles = filter_poles(params, constraint.load, &options.capacitors);
+        }
+    }
+    Ok(combinations)
+}
+
+pub fn find_best_combinations(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+    k: usize,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    let v_src = v_src.into();
+    validate_inputs(&constraint, &v_src, &resistors)?;
+    if k == 0 {
+        return Ok(Vec::new());
+    }
+
+    let resistors = rc_param::without_zero_values(resistors);
+    let networks = rc_param::expand_networks(&resistors, 1);
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for r1_network in &networks {
+        for params in combinations_for_r1(r1_network, &networks, &constraint, &v_src) {
+            if !has_finite_vref(&params) {
+                return Err(SolverError::NonFiniteValue);
+            }
+            heap.push(params);
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+    }
+    if heap.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    Ok(heap.into_sorted_vec())
+}
+
+// Widest gap between neighbouring values, e.g. about 1.47 for E6.
+fn coarse_step(resistors: &[Resistor]) -> f64 {
+    let mut values = resistors.iter().map(|r| r.get_value()).collect::<Vec<_>>();
+    values.sort_by(f64::total_cmp);
+    values
+        .windows(2)
+        .map(|w| w[1] / w[0])
+        .filter(|step| step.is_finite())
+        .fold(None, |widest: Option<f64>, step| {
+            Some(widest.map_or(step, |w| w.max(step)))
+        })
+        .unwrap_or(10.0)
+}
+
+// Walks one side of the Vref crossing for a fixed R1. Vref moves away from
+// the target with every step, so the walk stops once it leaves the band or,
+// with `keep` results held, once the error is worse than all of them.
+fn refine_side(
+    r1: &Resistor,
+    fine: &[Resistor],
+    indices: impl Iterator<Item = usize>,
+    constraint: &Constraint,
+    v_src: &VrefSource,
+    keep: usize,
+    heap: &mut BinaryHeap<CircuitParameters>,
+) -> Result<usize, SolverError> {
+    let mut examined = 0;
+    for j in indices {
+        let (r2_v, _, _) = lower_arm(&fine[j], constraint.load);
+        let vref = r2_v / (r1.get_value() + r2_v) * v_src.value();
+        if vref < constraint.voltage.min() || vref > constraint.voltage.max() {
+            break;
+        }
+        let error = (vref - constraint.voltage.value).powi(2);
+        if heap.len() == keep
+            && heap
+                .peek()
+                .is_some_and(|worst| error > worst.vref_error.value().powi(2))
+        {
+            break;
+        }
+        examined += 1;
+        let Some(params) = combination(
+            &Network::Single(*r1),
+            &Network::Single(fine[j]),
+            constraint,
+            v_src,
+        ) else {
+            continue;
+        };
+        if !has_finite_vref(&params) {
+            return Err(SolverError::NonFiniteValue);
+        }
+        heap.push(params);
+        if heap.len() > keep {
+            heap.pop();
+        }
+    }
+    Ok(examined)
+}
+
+// Returns the best `keep` pairs of the fine list (e.g. E96), the same as
+// find_best_combinations on it, without trying every pair. Vref rises with
+// R2, so for each R1 a binary search finds where it crosses the target and
+// only the R2 values whose error could still rank are visited. The coarse
+// list (e.g. E6) only decides which R1 values go first: those within half a
+// coarse step of a coarse winner, in every decade, fill the ranking with
+// good pairs early so most other R1 values end after the binary search.
+pub fn find_combinations_refined(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    coarse: Vec<Resistor>,
+    fine: Vec<Resistor>,
+    keep: usize,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    let v_src = v_src.into();
+    validate_inputs(&constraint, &v_src, &fine)?;
+    if keep == 0 {
+        return Ok(Vec::new());
+    }
+    let coarse = rc_param::without_zero_values(coarse);
+    let seeds = match find_best_combinations(constraint, v_src, coarse.clone(), keep) {
+        Ok(seeds) => seeds,
+        Err(SolverError::NoCombinationFound | SolverError::EmptyResistorList) => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    let half_step = coarse_step(&coarse).sqrt();
+
+    let mut fine = rc_param::without_zero_values(fine);
+    fine.sort_by(|a, b| a.get_value().total_cmp(&b.get_value()));
+    let (lowest, highest) = match (fine.first(), fine.last()) {
+        (Some(lo), Some(hi)) => (lo.get_value(), hi.get_value()),
+        _ => return Err(SolverError::NoCombinationFound),
+    };
+    let near = |center: f64| {
+        let lo = fine.partition_point(|r| r.get_value() < center / half_step);
+        let hi = fine.partition_point(|r| r.get_value() <= center * half_step);
+        lo..hi
+    };
+    let decades = (highest / lowest).log10().ceil() as i32;
+    let mut queued = vec![false; fine.len()];
+    let mut order = Vec::with_capacity(fine.len());
+    for seed in &seeds {
+        for shift in -decades..=decades {
+            for i in near(seed.r1.get_value() * 10f64.powi(shift)) {
+                if !queued[i] {
+                    queued[i] = true;
+                    order.push(i);
+                }
+            }
+        }
+    }
+    order.extend((0..fine.len()).filter(|&i| !queued[i]));
+
+    let target = constraint.voltage.value;
+    let mut heap = BinaryHeap::with_capacity(keep + 1);
+    let mut examined = 0;
+    for i in order {
+        let r1 = fine[i];
+        let crossing = fine.partition_point(|r2| {
+            let (r2_v, _, _) = lower_arm(r2, constraint.load);
+            r2_v / (r1.get_value() + r2_v) * v_src.value() < target
+        });
+        let above = crossing..fine.len();
+        examined += refine_side(&r1, &fine, above, &constraint, &v_src, keep, &mut heap)?;
+        let below = (0..crossing).rev();
+        examined += refine_side(&r1, &fine, below, &constraint, &v_src, keep, &mut heap)?;
+    }
+    log::debug!(
+        "refined {} coarse seeds, examining {} of {} fine pairs",
+        seeds.len(),
+        examined,
+        fine.len() * fine.len()
+    );
+    if heap.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    Ok(heap.into_sorted_vec())
+}
+
+pub fn find_distinct_combinations(
+    constraint: Constraint,
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+    epsilon: f64,
+) -> Result<Vec<CircuitParameters>, SolverError> {
+    let mut combinations = find_combinations(constraint, v_src, resistors)?;
+    combinations.sort_unstable_by(|a, b| a.vref.value.total_cmp(&b.vref.value));
+
+    let total = |p: &CircuitParameters| p.r1.get_value() + p.r2.get_value();
+    let mut distinct: Vec<CircuitParameters> = Vec::new();
+    let mut group_start = f64::NEG_INFINITY;
+    for params in combinations {
+        match distinct.last_mut() {
+            Some(best) if params.vref.value - group_start <= epsilon => {
+                if total(&params) < total(best) {
+                    *best = params;
+                }
+            }
+            _ => {
+                group_start = params.vref.value;
+                distinct.push(params);
+            }
+        }
+    }
+    distinct.sort_unstable();
+    Ok(distinct)
+}
+
+// Report for a known pair. There is no target, so vref_error is zero.
+pub fn evaluate_divider(r1: &Resistor, r2: &Resistor, v_src: &Voltage) -> CircuitParameters {
+    let params = divider_parameters(
+        &Network::Single(*r1),
+        &Network::Single(*r2),
+        None,
+        &VrefSource::from(*v_src),
+        0.0,
+    );
+    CircuitParameters {
+        vref_error: Volts(0.0),
+        ..params
+    }
+}
+
+// R2 is a fixed part in series with the pot used as a rheostat. Vref rises
+// with R2, so the range runs from the pot at zero with R2 low and R1 high to
+// the pot at full scale with R2 high and R1 low. The typical value is at the
+// pot's current wiper setting.
+pub fn trim_range(
+    r1: &Resistor,
+    r2_fixed: &Resistor,
+    pot: &Potentiometer,
+    v_src: &Voltage,
+) -> Voltage {
+    let ratio = |r1: f64, r2: f64| r2 / (r1 + r2);
+    let r2_typical = r2_fixed.get_value() + pot.get_value() * pot.get_wiper();
+    Voltage::new_by_values(
+        v_src.value * ratio(r1.get_value(), r2_typical),
+        v_src.min() * ratio(r1.max(), r2_fixed.min()),
+        v_src.max() * ratio(r1.min(), r2_fixed.max() + pot.max()),
+    )
+}
+
+// The current limit and quiescent power budget set the lowest usable total,
+// the Thevenin limit the highest: R_th = R_total * r * (1 - r) for ratio r.
+// None when those limits leave no window at all.
+pub fn suggest_resistance_window(
+    constraint: &Constraint,
+    v_src: impl Into<VrefSource>,
+) -> Option<(Ohms, Ohms)> {
+    let v_src = v_src.into();
+    let by_current = v_src.max() / constraint.max_current.value();
+    let by_power = constraint
+        .max_quiescent_power
+        .map_or(0.0, |p| v_src.max().powi(2) / p.value());
+    let ratio = constraint.voltage.value / v_src.value();
+    let max_total = constraint
+        .max_thevenin_resistance
+        .map_or(f64::INFINITY, |r| r.value() / (ratio * (1.0 - ratio)));
+    let min_total = by_current.max(by_power);
+    (min_total <= max_total).then_some((Ohms(min_total), Ohms(max_total)))
+}
+
+// ratio is Vref / Vsrc, i.e. R2 / (R1 + R2).
+pub fn ideal_divider(ratio: f64, total: f64) -> (f64, f64) {
+    let r2 = ratio * total;
+    (total - r2, r2)
+}
+
+pub fn ideal_divider_snapped(ratio: f64, total: f64, series: Series) -> (Resistor, Resistor) {
+    let (r1, r2) = ideal_divider(ratio, total);
+    (
+        rc_param::nearest_e_series(r1, series),
+        rc_param::nearest_e_series(r2, series),
+    )
+}
+
+pub fn output_current(k: &RangedValue<f64>, vref: &Voltage) -> RangedValue<f64> {
+    k.mul(vref)
+}
+
+pub fn output_current_at_typical_vref(k: &RangedValue<f64>, vref: &Voltage) -> RangedValue<f64> {
+    let typical = RangedValue::new(vref.value, vref.value, vref.value);
+    k.mul(&typical)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rc_param::small_test_resistors;
+
+    fn target(value: f64, min: f64, max: f64) -> Constraint {
+        Constraint::builder()
+            .voltage(Voltage::new_by_values(value, min, max))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn finds_the_only_pair_in_band() {
+        let v_src = Voltage::new_by_allowance(3.0, 0.0);
+        let results =
+            find_combinations(target(1.0, 0.9, 1.1), v_src, small_test_resistors()).unwrap();
+        assert_eq!(results.len(), 1);
+        let params = &results[0];
+        assert_eq!(params.r1.get_value(), 2e3);
+        assert_eq!(params.r2.get_value(), 1e3);
+        assert!((params.vref.value - 1.0).abs() < 1e-12);
+        assert!(params.vref_error.value().abs() < 1e-12);
+        // 990 / (2020 + 990) and 1010 / (1980 + 1010) of 3 V
+        assert!((params.vref.min() - 2970.0 / 3010.0).abs() < 1e-12);
+        assert!((params.vref.max() - 3030.0 / 2990.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ranks_by_error_then_total_resistance() {
+        let v_src = Voltage::new_by_allowance(3.0, 0.0);
+        let results =
+            find_combinations(target(1.4, 0.0, 3.0), v_src, small_test_resistors()).unwrap();
+        let pairs = results
+            .iter()
+            .map(|p| (p.r1.get_value(), p.r2.get_value()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pairs,
+            vec![
+                (1e3, 1e3),
+                (2e3, 2e3),
+                (3e3, 3e3),
+                (3e3, 2e3),
+                (2e3, 1e3),
+                (2e3, 3e3),
+                (1e3, 2e3),
+                (3e3, 1e3),
+                (1e3, 3e3),
+            ]
+        );
+    }
+
+    #[test]
+    fn current_limit_uses_the_top_of_the_supply_band() {
+        let resistors = Resistor::from_values(&[10e3], 0.0);
+        let constraint = Constraint::builder()
+            .voltage(Voltage::new_by_values(2.5, 2.0, 3.0))
+            .max_current(Amps(255e-6))
+            .build()
+            .unwrap();
+        // 5 V / 20 kΩ = 250 µA passes, but 5.25 V / 20 kΩ = 262.5 µA does not.
+        let exact = Voltage::new_by_allowance(5.0, 0.0);
+        assert_eq!(
+            find_combinations(constraint, exact, resistors.clone())
+                .unwrap()
+                .len(),
+            1
+        );
+        let banded = Voltage::new_by_allowance(5.0, 0.05);
+        assert_eq!(
+            find_combinations(constraint, banded, resistors).err(),
+            Some(SolverError::NoCombinationFound)
+        );
+    }
+
+    #[test]
+    fn ignores_zero_ohm_parts() {
+        let v_src = Voltage::new_by_allowance(3.0, 0.0);
+        let mut resistors = small_test_resistors();
+        resistors.push(Resistor::new(0.0, 0.01));
+        let with_zero = find_combinations(target(1.4, 0.0, 3.0), v_src, resistors).unwrap();
+        let without =
+            find_combinations(target(1.4, 0.0, 3.0), v_src, small_test_resistors()).unwrap();
+        assert_eq!(with_zero.len(), without.len());
+        assert!(with_zero.iter().all(|p| p.vref.value.is_finite()));
+    }
+
+    #[test]
+    fn evaluate_divider_has_no_error_term() {
+        let r = Resistor::new(10e3, 0.01);
+        let params = evaluate_divider(&r, &r, &Voltage::new_by_allowance(5.0, 0.05));
+        assert_eq!(params.vref.value, 2.5);
+        assert_eq!(params.vref_error, Volts(0.0));
+    }
+
+    #[test]
+    fn load_tolerance_widens_the_vref_bounds() {
+        let v_src = Voltage::new_by_allowance(5.0, 0.0);
+        let resistors = Resistor::from_values(&[10e3], 0.01);
+        let with_load = |tolerance: f64| {
+            let constraint = Constraint::builder()
+                .voltage(Voltage::new_by_values(1.7, 0.0, 5.0))
+                .load(Resistor::new(10e3, tolerance))
+                .build()
+                .unwrap();
+            find_combinations(constraint, v_src, resistors.clone())
+                .unwrap()
+                .remove(0)
+                .vref
+        };
+        let exact = with_load(0.0);
+        let loose = with_load(0.05);
+        assert_eq!(loose.value, exact.value);
+        assert!(loose.min() < exact.min() && loose.max() > exact.max());
+        // Highest output: R1 low, R2 and the load both high.
+        let lower_max = parallel(10.1e3, 10.5e3);
+        assert!((loose.max() - lower_max / (9.9e3 + lower_max) * 5.0).abs() < 1e-12);
+        let lower_min = parallel(9.9e3, 9.5e3);
+        assert!((loose.min() - lower_min / (10.1e3 + lower_min) * 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn refined_search_matches_the_full_fine_search() {
+        let v_src = Voltage::new_by_allowance(5.0, 0.25);
+        let coarse = rc_param::get_series_resistor_list(Series::E6, 0.01);
+        let fine = rc_param::get_series_resistor_list(Series::E96, 0.01);
+        let pairs = |results: &[CircuitParameters]| {
+            results
+                .iter()
+                .map(|p| (p.r1.get_value(), p.r2.get_value()))
+                .collect::<Vec<_>>()
+        };
+        // Pairs the seeded window used to miss: 11.5k/18.7k at -3.97 mV and
+        // the exact 93.1k/29.4k.
+        for (vref, (r1, r2)) in [(3.1, (11.5e3, 18.7e3)), (1.2, (93.1e3, 29.4e3))] {
+            let constraint = Constraint::builder()
+                .voltage(Voltage::new_by_values(vref, vref * 0.9, vref * 1.1))
+                .max_current(Amps(5e-4))
+                .build()
+                .unwrap();
+            let full = find_best_combinations(constraint, v_src, fine.clone(), 10).unwrap();
+            assert!(full
+                .iter()
+                .any(|p| (p.r1.get_value() - r1).abs() < 1e-6
+                    && (p.r2.get_value() - r2).abs() < 1e-6));
+            let refined =
+                find_combinations_refined(constraint, v_src, coarse.clone(), fine.clone(), 10)
+                    .unwrap();
+            assert_eq!(pairs(&refined), pairs(&full));
+        }
+
+        let constraint = Constraint::builder()
+            .voltage(Voltage::new_by_values(2.0, 1.8, 2.2))
+            .load(Resistor::new(47e3, 0.05))
+            .min_total_resistance(Ohms(20e3))
+            .max_total_resistance(Ohms(60e3))
+            .build()
+            .unwrap();
+        for keep in [1, 3, 25] {
+            let full = find_best_combinations(constraint, v_src, fine.clone(), keep).unwrap();
+            let refined =
+                find_combinations_refined(constraint, v_src, coarse.clone(), fine.clone(), keep)
+                    .unwrap();
+            assert_eq!(pairs(&refined), pairs(&full));
+        }
+    }
+
+    #[test]
+    fn suggests_a_window_only_when_one_exists() {
+        let v_src = Voltage::new_by_allowance(5.0, 0.05);
+        let constraint = |r_th: f64| {
+            Constraint::builder()
+                .voltage(Voltage::new_by_values(2.5, 2.4, 2.6))
+                .max_current(Amps(100e-6))
+                .max_thevenin_resistance(Ohms(r_th))
+                .build()
+                .unwrap()
+        };
+        // 5.25 V / 100 µA = 52.5 kΩ at least, R_th / 0.25 at most.
+        let (min, max) = suggest_resistance_window(&constraint(20e3), v_src).unwrap();
+        assert!((min.value() - 52.5e3).abs() < 1e-6);
+        assert!((max.value() - 80e3).abs() < 1e-6);
+        assert_eq!(suggest_resistance_window(&constraint(10e3), v_src), None);
+    }
+}
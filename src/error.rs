@@ -0,0 +1,122 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverError {
+    EmptyResistorList,
+    EmptyComponentList,
+    NonFiniteValue,
+    NoCombinationFound,
+    InvalidProblem,
+    UnsupportedLoad,
+    InvalidAdcBits(u32),
+    InvalidConstraint(ConstraintError),
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::EmptyResistorList => write!(f, "resistor list is empty"),
+            SolverError::EmptyComponentList => write!(f, "component list is empty"),
+            SolverError::NonFiniteValue => write!(f, "input contains a non-finite value"),
+            SolverError::NoCombinationFound => {
+                write!(f, "no resistor combination satisfies the constraint")
+            }
+            SolverError::InvalidProblem => write!(f, "problem specification is invalid"),
+            SolverError::UnsupportedLoad => write!(f, "this solver does not model a load"),
+            SolverError::InvalidAdcBits(bits) => {
+                write!(f, "ADC resolution of {bits} bits is outside 1..=32")
+            }
+            SolverError::InvalidConstraint(e) => write!(f, "invalid constraint: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintError {
+    MissingVoltage,
+    InvalidVoltageRange,
+    InvalidCurrentLimit,
+    InvalidResistanceWindow,
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintError::MissingVoltage => write!(f, "target voltage is not set"),
+            ConstraintError::InvalidVoltageRange => {
+                write!(f, "target voltage must satisfy min <= typical <= max")
+            }
+            ConstraintError::InvalidCurrentLimit => {
+                write!(f, "current limit must be a positive number")
+            }
+            ConstraintError::InvalidResistanceWindow => {
+                write!(f, "total resistance window must satisfy 0 <= min <= max")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSeriesError(pub String);
+
+impl fmt::Display for ParseSeriesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown series `{}`, expected one of E3..E192", self.0)
+    }
+}
+
+impl std::error::Error for ParseSeriesError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseValueError(pub String);
+
+impl fmt::Display for ParseValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot parse `{}` as a component value", self.0)
+    }
+}
+
+impl std::error::Error for ParseValueError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoltageError {
+    NonFiniteValue,
+    InvalidRange,
+}
+
+impl fmt::Display for VoltageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoltageError::NonFiniteValue => write!(f, "voltage contains a non-finite value"),
+            VoltageError::InvalidRange => write!(f, "voltage must satisfy min <= value <= max"),
+        }
+    }
+}
+
+impl std::error::Error for VoltageError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeError;
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "range must satisfy min <= value <= max")
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToleranceError(pub f64);
+
+impl fmt::Display for ToleranceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tolerance {} is outside [0, 1)", self.0)
+    }
+}
+
+impl std::error::Error for ToleranceError {}
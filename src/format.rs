@@ -0,0 +1,300 @@
+use crate::divider::CircuitParameters;
+use crate::error::ParseValueError;
+use crate::rc_param::{PassiveComponent, Resistor};
+
+const SI_PREFIXES: [&str; 9] = ["p", "n", "µ", "m", "", "k", "M", "G", "T"];
+const SI_PREFIX_OFFSET: i32 = 4;
+
+fn si_scaled(value: f64) -> (f64, &'static str) {
+    si_scaled_within(value, "p", "T", None)
+}
+
+// Rounds to one decimal, or to `sig_figs` significant figures when given.
+fn round_scaled(scaled: f64, sig_figs: Option<u32>) -> f64 {
+    match sig_figs {
+        None => (scaled * 10.0).round() / 10.0,
+        Some(n) => {
+            let shift = n as i32 - 1 - scaled.abs().log10().floor() as i32;
+            if shift >= 0 {
+                (scaled * 10f64.powi(shift)).round() / 10f64.powi(shift)
+            } else {
+                (scaled / 10f64.powi(-shift)).round() * 10f64.powi(-shift)
+            }
+        }
+    }
+}
+
+fn si_scaled_within(
+    value: f64,
+    lowest: &str,
+    highest: &str,
+    sig_figs: Option<u32>,
+) -> (f64, &'static str) {
+    if value == 0.0 || !value.is_finite() {
+        return (value, "");
+    }
+    let position = |prefix: &str| SI_PREFIXES.iter().position(|p| *p == prefix).unwrap() as i32;
+    let (min_index, max_index) = (position(lowest), position(highest));
+    let mut index =
+        ((value.abs().log10() / 3.0).floor() as i32 + SI_PREFIX_OFFSET).clamp(min_index, max_index);
+    loop {
+        let scaled = value / 10f64.powi(3 * (index - SI_PREFIX_OFFSET));
+        let rounded = round_scaled(scaled, sig_figs);
+        if rounded.abs() >= 1000.0 && index < max_index {
+            index += 1;
+            continue;
+        }
+        return (rounded, SI_PREFIXES[index as usize]);
+    }
+}
+
+pub fn engineering_notation(value: f64, unit: &str) -> String {
+    let (scaled, prefix) = si_scaled(value);
+    format!("{scaled} {prefix}{unit}")
+}
+
+pub fn prefixed_for_resistance(val: f64) -> (f64, String) {
+    let (scaled, prefix) = si_scaled(val);
+    (scaled, prefix.to_string())
+}
+
+// E96 and E192 parts need three significant figures, e.g. 4.87 kΩ.
+pub fn prefixed_for_resistance_sig(val: f64, sig_figs: u32) -> (f64, String) {
+    let (scaled, prefix) = si_scaled_within(val, "p", "T", Some(sig_figs.max(1)));
+    (scaled, prefix.to_string())
+}
+
+pub fn prefixed_for_capacitance(val: f64) -> (f64, String) {
+    let (scaled, prefix) = si_scaled_within(val, "p", "m", None);
+    (scaled, prefix.to_string())
+}
+
+// RKM style as used in schematic value fields: the prefix takes the place of
+// the decimal point, with R for plain ohms, e.g. 4k7, 10k, 4R7, 100R, R047.
+pub fn kicad_value(r: &Resistor) -> String {
+    let (scaled, prefix) = si_scaled_within(r.get_value(), "", "G", Some(3));
+    let marker = if prefix.is_empty() { "R" } else { prefix };
+    let digits = scaled.to_string();
+    match digits.split_once('.') {
+        Some(("0", fraction)) => format!("{marker}{fraction}"),
+        Some((whole, fraction)) => format!("{whole}{marker}{fraction}"),
+        None => format!("{digits}{marker}"),
+    }
+}
+
+// SPICE reads "M" as milli, so mega is spelled "Meg" and micro "u".
+pub(crate) fn spice_value(val: f64) -> String {
+    let (scaled, prefix) = si_scaled_within(val, "p", "T", Some(6));
+    let prefix = match prefix {
+        "µ" => "u",
+        "M" => "Meg",
+        p => p,
+    };
+    format!("{scaled}{prefix}")
+}
+
+// Aligned table of results; current is the typical current through R2.
+pub fn format_results(results: &[CircuitParameters]) -> String {
+    let resistance = |v: f64| {
+        let (scaled, prefix) = prefixed_for_resistance_sig(v, 3);
+        format!("{scaled} {prefix}Ω")
+    };
+    let header = [
+        "R1", "R2", "Vref", "Vref min", "Vref max", "Error %", "Current",
+    ]
+    .map(String::from);
+    let rows = results
+        .iter()
+        .map(|params| {
+            let target = params.vref.value - params.vref_error.value();
+            [
+                resistance(params.r1.get_value()),
+                resistance(params.r2.get_value()),
+                format!("{:.4} V", params.vref.value),
+                format!("{:.4} V", params.vref.min()),
+                format!("{:.4} V", params.vref.max()),
+                format!("{:+.3}", params.vref_error.value() / target * 100.0),
+                engineering_notation(params.vref.value / params.r2.get_value(), "A"),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = header.clone().map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:>width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        table.push_str(&line);
+        table.push('\n');
+    }
+    table
+}
+
+fn prefix_exponent(c: char) -> Option<i32> {
+    match c {
+        'p' => Some(-12),
+        'n' => Some(-9),
+        'u' | 'U' | 'µ' | 'μ' => Some(-6),
+        'm' => Some(-3),
+        'k' | 'K' => Some(3),
+        'M' => Some(6),
+        'G' | 'g' => Some(9),
+        'T' | 't' => Some(12),
+        _ => None,
+    }
+}
+
+// Accepts "4.7k", "470m", "4k7" and "4n7" style values. `decimal_marks`
+// are extra unit-less letters that may stand in for the point, like R in 4R7.
+fn parse_prefixed(
+    input: &str,
+    units: &[&str],
+    decimal_marks: &[char],
+) -> Result<f64, ParseValueError> {
+    let error = || ParseValueError(input.to_string());
+    let compact: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let lower = compact.to_lowercase();
+    let body = units
+        .iter()
+        .find(|unit| lower.ends_with(&unit.to_lowercase()) && lower.len() > unit.len())
+        .map_or(compact.as_str(), |unit| {
+            let cut = compact
+                .char_indices()
+                .rev()
+                .nth(unit.chars().count() - 1)
+                .map_or(0, |(i, _)| i);
+            &compact[..cut]
+        });
+
+    let marker = body
+        .char_indices()
+        .find(|(_, c)| prefix_exponent(*c).is_some() || decimal_marks.contains(c));
+    let (number, exponent) = match marker {
+        None => (body.to_string(), 0),
+        Some((i, c)) => {
+            let (head, tail) = (&body[..i], &body[i + c.len_utf8()..]);
+            let exponent = prefix_exponent(c).unwrap_or(0);
+            if tail.is_empty() {
+                (head.to_string(), exponent)
+            } else if !head.contains('.') && tail.chars().all(|d| d.is_ascii_digit()) {
+                (format!("{head}.{tail}"), exponent)
+            } else {
+                return Err(error());
+            }
+        }
+    };
+    let mantissa = number.parse::<f64>().map_err(|_| error())?;
+    let value = if exponent < 0 {
+        mantissa / 10f64.powi(-exponent)
+    } else {
+        mantissa * 10f64.powi(exponent)
+    };
+    // Component values are magnitudes; "-4k7", "inf" and "1e308k" are typos.
+    if !(value.is_finite() && value >= 0.0) {
+        return Err(error());
+    }
+    Ok(value)
+}
+
+pub fn parse_resistance(s: &str) -> Result<f64, ParseValueError> {
+    parse_prefixed(s, &["ohms", "ohm", "Ω"], &['R', 'r'])
+}
+
+pub fn parse_capacitance(s: &str) -> Result<f64, ParseValueError> {
+    parse_prefixed(s, &["F"], &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_decade_maps_to_one_prefix() {
+        assert_eq!(prefixed_for_resistance(999.0), (999.0, "".to_string()));
+        assert_eq!(prefixed_for_resistance(1000.0), (1.0, "k".to_string()));
+        // One decimal place, so 1001 Ω rounds to 1.0 kΩ.
+        assert_eq!(prefixed_for_resistance(1001.0), (1.0, "k".to_string()));
+        assert_eq!(prefixed_for_resistance(470_000.0), (470.0, "k".to_string()));
+        assert_eq!(prefixed_for_resistance(0.47), (470.0, "m".to_string()));
+    }
+
+    #[test]
+    fn kicad_value_puts_the_prefix_at_the_point() {
+        let kicad = |value: f64| kicad_value(&Resistor::new(value, 0.01));
+        assert_eq!(kicad(4.7e3), "4k7");
+        assert_eq!(kicad(10e3), "10k");
+        assert_eq!(kicad(4.99e3), "4k99");
+        assert_eq!(kicad(1e6), "1M");
+        assert_eq!(kicad(4.7), "4R7");
+        assert_eq!(kicad(100.0), "100R");
+        assert_eq!(kicad(0.047), "R047");
+    }
+
+    #[test]
+    fn engineering_notation_uses_the_decade_prefix() {
+        assert_eq!(engineering_notation(1000.0, "Ω"), "1 kΩ");
+        assert_eq!(engineering_notation(1000.0, "A"), "1 kA");
+        assert_eq!(engineering_notation(250e-6, "A"), "250 µA");
+    }
+
+    #[test]
+    fn capacitance_prefixes_stop_at_milli() {
+        assert_eq!(prefixed_for_capacitance(4.7e-9), (4.7, "n".to_string()));
+        assert_eq!(prefixed_for_capacitance(100e-12), (100.0, "p".to_string()));
+        assert_eq!(prefixed_for_capacitance(10e-6), (10.0, "µ".to_string()));
+        assert_eq!(prefixed_for_capacitance(1.0), (1000.0, "m".to_string()));
+    }
+
+    #[test]
+    fn parses_prefixed_and_marked_values() {
+        let cases = [
+            ("4k7", 4700.0),
+            ("4.7k", 4700.0),
+            ("4.7K", 4700.0),
+            ("470m", 0.47),
+            ("1M", 1e6),
+            ("4R7", 4.7),
+            ("4r7", 4.7),
+            ("R47", 0.47),
+            ("10 kΩ", 10e3),
+            ("100 ohms", 100.0),
+            ("2k2 Ohm", 2200.0),
+        ];
+        for (input, expected) in cases {
+            let value = parse_resistance(input).unwrap();
+            assert!((value - expected).abs() < 1e-9 * expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn rejects_bad_suffixes_and_negative_values() {
+        for input in [
+            "4x7", "4.7kk", "4.7.k", "-4k7", "-10", "inf", "NaN", "1e308k", "",
+        ] {
+            assert_eq!(
+                parse_resistance(input),
+                Err(ParseValueError(input.to_string())),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_capacitance() {
+        assert!((parse_capacitance("100nF").unwrap() - 100e-9).abs() < 1e-18);
+        assert!((parse_capacitance("4n7").unwrap() - 4.7e-9).abs() < 1e-18);
+        assert!((parse_capacitance("10 uF").unwrap() - 10e-6).abs() < 1e-15);
+        assert!((parse_capacitance("1µ").unwrap() - 1e-6).abs() < 1e-15);
+        assert!(parse_capacitance("4R7").is_err());
+        assert!(parse_capacitance("-1uF").is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use crate::error::SolverError;
+use crate::ranged::{Gain, RangedType, RangedValue};
+use crate::rc_param::{self, PassiveComponent, Resistor};
+
+// Non-inverting amplifier: gain = 1 + Rf / Rg
+#[derive(Debug)]
+pub struct GainNetworkParameters {
+    pub rf: Resistor,
+    pub rg: Resistor,
+    pub gain: Gain,
+    pub gain_error: f64,
+}
+
+pub fn find_gain_network(
+    target_gain: RangedValue<f64>,
+    resistors: Vec<Resistor>,
+) -> Result<Vec<GainNetworkParameters>, SolverError> {
+    if resistors.is_empty() {
+        return Err(SolverError::EmptyResistorList);
+    }
+    if !resistors
+        .iter()
+        .all(|r| r.min().is_finite() && r.max().is_finite())
+    {
+        return Err(SolverError::NonFiniteValue);
+    }
+
+    let resistors = rc_param::without_zero_values(resistors);
+    let mut results: Vec<GainNetworkParameters> = resistors
+        .iter()
+        .flat_map(|rf| resistors.iter().map(move |rg| (rf, rg)))
+        .filter_map(|(rf, rg)| {
+            let gain = 1.0 + rf.get_value() / rg.get_value();
+            if gain < target_gain.get_min() || gain > target_gain.get_max() {
+                return None;
+            }
+            Some(GainNetworkParameters {
+                rf: *rf,
+                rg: *rg,
+                gain: RangedValue::new(gain, 1.0 + rf.min() / rg.max(), 1.0 + rf.max() / rg.min()),
+                gain_error: gain - target_gain.get_typical_value(),
+            })
+        })
+        .collect();
+    if results.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    results.sort_by(|a, b| {
+        a.gain_error.abs().total_cmp(&b.gain_error.abs()).then(
+            (a.rf.get_value() + a.rg.get_value()).total_cmp(&(b.rf.get_value() + b.rg.get_value())),
+        )
+    });
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_zero_ohm_parts() {
+        let resistors = Resistor::from_values(&[0.0, 1e3, 2e3], 0.01);
+        // Rf = Rg = 0 would give 1 + 0/0, a NaN gain that no bound rejects.
+        let results = find_gain_network(RangedValue::new(2.0, 1.0, 10.0), resistors).unwrap();
+        assert!(results
+            .iter()
+            .all(|r| r.gain.get_typical_value().is_finite()));
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].gain.get_typical_value(), 2.0);
+    }
+}
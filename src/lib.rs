@@ -0,0 +1,63 @@
+mod adc;
+mod capacitive;
+mod clamp;
+mod config;
+mod cost;
+mod csv;
+mod divider;
+mod error;
+mod format;
+mod gain;
+mod monte_carlo;
+mod noise;
+#[cfg(feature = "serde")]
+mod problem;
+mod ranged;
+mod ratio;
+mod rc_filter;
+pub mod rc_param;
+mod spice;
+mod tapped;
+mod units;
+
+pub use crate::adc::{find_adc_divider, AdcDividerParameters};
+pub use crate::capacitive::{find_capacitive_combinations, CapacitiveDividerParameters};
+pub use crate::clamp::{find_series_resistor, SeriesResistorParameters};
+pub use crate::config::{Config, ConfigError};
+pub use crate::cost::{rank_by_cost, CostedParameters, PriceTable};
+pub use crate::csv::{write_csv, write_csv_with, CsvOptions};
+pub use crate::divider::{
+    bracketing_dividers, combinations_iter, combinations_within, evaluate_divider,
+    find_best_combinations, find_combinations, find_combinations_asym, find_combinations_by,
+    find_combinations_multi, find_combinations_refined, find_combinations_with,
+    find_combinations_with_stats, find_distinct_combinations, find_matched_thevenin, ideal_divider,
+    ideal_divider_snapped, output_current, output_current_at_typical_vref,
+    suggest_resistance_window, trim_range, CircuitParameters, Constraint, FilterPole, RankBy,
+    SearchOptions, SearchStats, VrefSource,
+};
+pub use crate::error::{
+    ConstraintError, ParseSeriesError, ParseValueError, RangeError, SolverError, ToleranceError,
+    VoltageError,
+};
+pub use crate::format::{
+    engineering_notation, format_results, kicad_value, parse_capacitance, parse_resistance,
+    prefixed_for_capacitance, prefixed_for_resistance, prefixed_for_resistance_sig,
+};
+pub use crate::gain::{find_gain_network, GainNetworkParameters};
+pub use crate::monte_carlo::{
+    monte_carlo, monte_carlo_with, rank_by_yield, yield_estimate, Distribution, MonteCarloOptions,
+    Rng,
+};
+pub use crate::noise::{integrated_noise, noise_power_tradeoff};
+#[cfg(feature = "serde")]
+pub use crate::problem::{solve_from_json, Problem};
+pub use crate::ranged::{Gain, RangedF64, RangedType, RangedValue, Voltage};
+pub use crate::ratio::{find_minimum_network, MinimumNetwork};
+pub use crate::rc_filter::{output_impedance_at, psrr, psrr_at, rc_cutoff, settling_time};
+pub use crate::rc_param::{
+    get_e_series_values, nearest_e_series, ratio_tempco, standard_resistance_values, Capacitor,
+    Inductor, Network, PassiveComponent, Potentiometer, Resistor, Series, Tolerance,
+};
+pub use crate::spice::to_spice_netlist;
+pub use crate::tapped::{find_three_resistor_combinations, TappedCircuitParameters};
+pub use crate::units::{Amps, Ohms, Volts, Watts};
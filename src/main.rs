@@ -1,261 +1,337 @@
-use std::cmp::Ordering;
+use std::path::PathBuf;
 
-use crate::rc_param::{PassiveComponent, Resistor};
+use clap::Parser;
+use voltage_div::rc_param::{self, PassiveComponent, Resistor, Series, Tolerance};
+use voltage_div::{
+    engineering_notation, find_best_combinations, find_combinations_with,
+    find_combinations_with_stats, output_current, output_current_at_typical_vref, Amps, Config,
+    Constraint, Gain, Ohms, RangedType, RangedValue, RankBy, SearchOptions, SearchStats,
+    SolverError, Voltage, Watts,
+};
 
-mod rc_param;
-
-fn prefixed_for_resistance(val: f64) -> (f64, String) {
-    match val {
-        x if (1.0..1000.0).contains(&x) => ((x * 10.0f64).round() / 10.0f64, "".to_string()),
-        x if x * 10f64.powi(-6) >= 1.0 => (
-            (x * 10f64.powi(-6) * 10.0f64).round() / 10.0f64,
-            "M".to_string(),
-        ),
-        x if x * 10f64.powi(-3) >= 1.0 => (
-            (x * 10f64.powi(-3) * 10.0f64).round() / 10.0f64,
-            "k".to_string(),
-        ),
-        x if x * 10f64.powi(3) >= 1.0 => (
-            (x * 10f64.powi(3) * 10.0f64).round() / 10.0f64,
-            "m".to_string(),
-        ),
-        x => (x, "".to_string()),
-    }
-}
-
-#[derive(Copy, Clone)]
-struct Constraint {
-    pub voltage: Voltage,
-    pub max_current: f64,
+// Command-line overrides; any flag left out keeps the value from `--config`
+// or the built-in default.
+#[derive(Parser)]
+#[command(name = "divide_v", about = "Find resistor pairs for a voltage divider")]
+struct Cli {
+    /// TOML file with the same keys as the flags, using underscores
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Supply voltage
+    #[arg(long, value_name = "V", value_parser = parse_vcc)]
+    vcc: Option<f64>,
+    /// Target output voltage
+    #[arg(long, value_name = "V")]
+    vref: Option<f64>,
+    /// Lowest acceptable output voltage
+    #[arg(long, value_name = "V")]
+    vref_min: Option<f64>,
+    /// Highest acceptable output voltage
+    #[arg(long, value_name = "V")]
+    vref_max: Option<f64>,
+    /// Worst-case divider current limit
+    #[arg(long, value_name = "A")]
+    max_current: Option<f64>,
+    /// Resistor tolerance as a fraction, e.g. 0.01
+    #[arg(long, value_name = "FRACTION", value_parser = parse_tolerance)]
+    tolerance: Option<f64>,
+    /// E series to draw resistor values from
+    #[arg(long, value_name = "E3|E6|E12|E24|E48|E96|E192")]
+    series: Option<Series>,
+    /// Number of results to print
+    #[arg(long, value_name = "N", value_parser = parse_count)]
+    count: Option<usize>,
 }
 
-trait RangedType {
-    type Item;
-    fn get_typical_value(&self) -> Self::Item;
-    fn get_min(&self) -> Self::Item;
-    fn get_max(&self) -> Self::Item;
+fn parse_vcc(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(v) if v.is_finite() && v > 0.0 => Ok(v),
+        Ok(_) => Err("must be a positive voltage".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Voltage {
-    pub value: f64,
-    min: f64,
-    max: f64,
+fn parse_tolerance(value: &str) -> Result<f64, String> {
+    let fraction = value.parse::<f64>().map_err(|e| e.to_string())?;
+    Tolerance::try_fraction(fraction)
+        .map(|t| t.as_fraction())
+        .map_err(|e| e.to_string())
 }
 
-impl RangedType for Voltage {
-    type Item = f64;
-
-    fn get_typical_value(&self) -> Self::Item {
-        self.value
+// Zero would print nothing and then report that no result passed.
+fn parse_count(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(count) => Ok(count),
+        Err(e) => Err(e.to_string()),
     }
+}
 
-    fn get_min(&self) -> Self::Item {
-        self.min
+impl Cli {
+    fn into_config(self) -> Result<Config, String> {
+        let mut config = match &self.config {
+            Some(path) => {
+                Config::from_file(path).map_err(|e| format!("{}: {e}", path.display()))?
+            }
+            None => Config::default(),
+        };
+        config.vcc = self.vcc.unwrap_or(config.vcc);
+        config.vref = self.vref.unwrap_or(config.vref);
+        config.vref_min = self.vref_min.unwrap_or(config.vref_min);
+        config.vref_max = self.vref_max.unwrap_or(config.vref_max);
+        config.max_current = self.max_current.unwrap_or(config.max_current);
+        config.tolerance = self.tolerance.unwrap_or(config.tolerance);
+        config.series = self.series.unwrap_or(config.series);
+        config.count = self.count.unwrap_or(config.count);
+        Ok(config)
     }
+}
 
-    fn get_max(&self) -> Self::Item {
-        self.max
-    }
+// The limit that keeps every pair out, as found by re-running the search.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Blocker {
+    VrefBand,
+    TotalResistance,
+    Current,
+    TotalResistanceAndCurrent,
+    Power,
+    Thevenin,
+    QuiescentPower,
 }
 
-impl Voltage {
-    pub fn new_by_allowance(value: f64, allowance: f64) -> Self {
-        Self {
-            value,
-            min: value * (1.0 - allowance),
-            max: value * (1.0 + allowance),
+impl Blocker {
+    fn hint(self) -> &'static str {
+        match self {
+            Blocker::VrefBand => "widen the Vref band with --vref-min/--vref-max",
+            Blocker::TotalResistance => {
+                "widen min_total_resistance/max_total_resistance in the config file"
+            }
+            Blocker::Current => "raise --max-current",
+            Blocker::TotalResistanceAndCurrent => {
+                "raise --max-current and widen the total resistance window"
+            }
+            Blocker::Power => "every in-band pair exceeds the resistor power limit",
+            Blocker::Thevenin => "every in-band pair exceeds the Thevenin resistance limit",
+            Blocker::QuiescentPower => "every in-band pair exceeds the quiescent power budget",
         }
     }
-
-    pub fn new_by_values(value: f64, min: f64, max: f64) -> Self {
-        Self { value, min, max }
-    }
-
-    pub fn min(&self) -> f64 {
-        self.min
-    }
-    pub fn max(&self) -> f64 {
-        self.max
-    }
 }
 
-#[derive(Copy, Clone, Debug)]
-struct RangedValue<T>
-where
-    T: Copy,
-{
-    pub value: T,
-    min: T,
-    max: T,
+// Pairs that got past the window, the current limit and the Vref band,
+// whether or not a later limit then turned them away.
+fn reached_band(stats: &SearchStats) -> usize {
+    stats.passed + stats.rejected_power + stats.rejected_thevenin + stats.rejected_quiescent_power
 }
 
-impl<T> RangedType for RangedValue<T>
-where
-    T: Copy,
-{
-    type Item = T;
-
-    fn get_typical_value(&self) -> Self::Item {
-        self.value
-    }
-
-    fn get_min(&self) -> Self::Item {
-        self.min
+fn find_blocker(constraint: &Constraint, vcc: Voltage, resistors: &[Resistor]) -> Blocker {
+    let stats = |constraint: Constraint| {
+        let mut stats = SearchStats::default();
+        let _ = find_combinations_with_stats(constraint, vcc, resistors.to_vec(), &mut stats);
+        stats
+    };
+    // Power, Thevenin and quiescent power are checked after the band, so any
+    // pair they rejected was otherwise acceptable.
+    let stats_as_given = stats(*constraint);
+    let late = [
+        (stats_as_given.rejected_power, Blocker::Power),
+        (stats_as_given.rejected_thevenin, Blocker::Thevenin),
+        (
+            stats_as_given.rejected_quiescent_power,
+            Blocker::QuiescentPower,
+        ),
+    ];
+    if let Some((_, blocker)) = late
+        .into_iter()
+        .filter(|(n, _)| *n > 0)
+        .max_by_key(|(n, _)| *n)
+    {
+        return blocker;
     }
-
-    fn get_max(&self) -> Self::Item {
-        self.max
+    // The window and the current limit come before the band; lift them in
+    // turn to see which one hides the in-band pairs.
+    let without_window = Constraint {
+        min_total_resistance: Ohms(0.0),
+        max_total_resistance: Ohms(f64::INFINITY),
+        ..*constraint
+    };
+    let without_current = Constraint {
+        max_current: Amps(f64::INFINITY),
+        ..*constraint
+    };
+    let without_either = Constraint {
+        max_current: Amps(f64::INFINITY),
+        ..without_window
+    };
+    if reached_band(&stats(without_window)) > 0 {
+        Blocker::TotalResistance
+    } else if reached_band(&stats(without_current)) > 0 {
+        Blocker::Current
+    } else if reached_band(&stats(without_either)) > 0 {
+        Blocker::TotalResistanceAndCurrent
+    } else {
+        Blocker::VrefBand
     }
 }
 
-impl<T> RangedValue<T>
-where
-    T: Copy,
-{
-    pub fn new(
-        value: <Self as RangedType>::Item,
-        min: <Self as RangedType>::Item,
-        max: <Self as RangedType>::Item,
-    ) -> Self {
-        Self { value, min, max }
+// Re-run with every limit lifted to show how close the nearest candidate
+// came, then name the limit that rejected the pairs.
+fn report_nearest(constraint: &Constraint, vcc: Voltage, resistors: Vec<Resistor>) {
+    let target = constraint.voltage.value;
+    let mut relaxed = Constraint::builder().voltage(Voltage::new_by_values(
+        target,
+        target.min(0.0),
+        target.max(vcc.max()),
+    ));
+    if let Some(load) = constraint.load {
+        relaxed = relaxed.load(load);
     }
+    let nearest = relaxed
+        .build()
+        .ok()
+        .and_then(|relaxed| find_best_combinations(relaxed, vcc, resistors.clone(), 1).ok())
+        .and_then(|best| best.into_iter().next());
+    if let Some(nearest) = nearest {
+        let current = vcc.max() / (nearest.r1.min() + nearest.r2.min());
+        eprintln!(
+            "nearest: R1 {}, R2 {}, Vref {} ({:+} V from target), worst-case current {} A",
+            engineering_notation(nearest.r1.get_value(), "Ω"),
+            engineering_notation(nearest.r2.get_value(), "Ω"),
+            nearest.vref.value,
+            nearest.vref_error,
+            current
+        );
+    }
+    eprintln!("hint: {}", find_blocker(constraint, vcc, &resistors).hint());
 }
 
-type Gain = RangedValue<f64>;
-
-#[derive(Debug)]
-struct CircuitParameters {
-    pub r1: Resistor,
-    pub r2: Resistor,
-    pub vref: Voltage,
-    pub vref_error: f64,
-}
-
-enum VrefSource {
-    Vcc(Voltage),
-    Regulator(Voltage),
-}
-
-fn find_combinations(
-    constraint: Constraint,
-    v_src: Voltage,
-    resistors: Vec<Resistor>,
-) -> Vec<CircuitParameters> {
-    let r1_resistors = resistors.to_vec();
-    let r2_resistors = resistors.to_vec();
-    let t = std::time::Instant::now();
-    let mut combinations = r1_resistors
-        .iter()
-        .flat_map(|r1| {
-            let r1_v = r1.get_value();
-            let r1_min = r1.min();
-            let r1_max = r1.max();
-            r2_resistors
-                .iter()
-                .filter(|&r2| {
-                    let r2_v = r2.get_value();
-                    let r2_min = r2.min();
-                    let max_curr = v_src.value / (r1_min + r2_min);
-                    let vref = r2_v / (r1_v + r2_v) * v_src.value;
-                    max_curr <= constraint.max_current
-                        && vref >= constraint.voltage.min()
-                        && vref <= constraint.voltage.max()
-                })
-                .map(|r2| {
-                    let r2_v = r2.get_value();
-                    let r2_min = r2.min();
-                    let r2_max = r2.max();
-                    let r = r2_v / (r1_v + r2_v);
-                    let vref = {
-                        let v_max = (r2_max / (r1_min + r2_max)) * v_src.max();
-                        let v_min = (r2_min / (r1_max + r2_min)) * v_src.min();
-                        Voltage::new_by_values(r * v_src.value, v_min, v_max)
-                    };
-                    let err = vref.value - constraint.voltage.value;
-                    CircuitParameters {
-                        r1: *r1,
-                        r2: *r2,
-                        vref,
-                        vref_error: err,
-                    }
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
-    dbg!(t.elapsed());
-    combinations.sort_unstable_by(|a, b| {
-        let x = a.vref_error.powi(2);
-        let y = b.vref_error.powi(2);
-        if x > y {
-            Ordering::Greater
-        } else if x < y {
-            Ordering::Less
-        } else if a.r1.get_value() + a.r2.get_value() > b.r1.get_value() + b.r2.get_value() {
-            Ordering::Greater
-        } else if a.r1.get_value() + a.r2.get_value() < b.r1.get_value() + b.r2.get_value() {
-            Ordering::Less
-        } else {
-            Ordering::Equal
+fn main() {
+    env_logger::init();
+    let config = match Cli::parse().into_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(2);
         }
-    });
-    combinations
-}
+    };
 
-fn main() {
-    let constraint = Constraint {
-        voltage: Voltage::new_by_values(2.0, 0.5, 4.0),
-        max_current: 5e-4,
+    let constraint = match Constraint::builder()
+        .voltage(Voltage::new_by_values(
+            config.vref,
+            config.vref_min,
+            config.vref_max,
+        ))
+        .max_current(Amps(config.max_current))
+        .max_resistor_power(Watts(0.0625))
+        .min_total_resistance(Ohms(config.min_total_resistance))
+        .max_total_resistance(Ohms(config.max_total_resistance))
+        .build()
+    {
+        Ok(constraint) => constraint,
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(2);
+        }
     };
-    let vcc: Voltage = Voltage::new_by_allowance(5.0f64, 5.0f64 * 0.050f64);
+    let vcc: Voltage = Voltage::new_by_allowance(config.vcc, config.vcc_tolerance);
     let i_out = 1.9f64;
 
-    let resistor_tolerance = 0.01;
-    let resistors = rc_param::get_resistor_list(resistor_tolerance);
-
-    let mut combinations = find_combinations(constraint, vcc, resistors);
+    let resistor_tolerance = config.tolerance;
+    let resistors = rc_param::get_series_resistor_list(config.series, resistor_tolerance);
+    let options = SearchOptions {
+        rank_by: RankBy::OutputCurrent,
+        ..SearchOptions::default()
+    };
+    let combinations = match find_combinations_with(constraint, vcc, resistors.clone(), &options) {
+        Ok(combinations) => combinations,
+        Err(SolverError::NoCombinationFound) => {
+            eprintln!("error: {}", SolverError::NoCombinationFound);
+            report_nearest(&constraint, vcc, resistors);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+    };
 
     let r_rs = Resistor::new(0.47, resistor_tolerance);
+    let r_rs = RangedValue::new(r_rs.get_value(), r_rs.min(), r_rs.max());
     let gain = Gain::new(1.0 / 5.0, 1.0 / 5.2, 1.0 / 4.8);
-    let k = RangedValue::new(
-        gain.get_typical_value() / r_rs.get_value(),
-        gain.get_min() / r_rs.max(),
-        gain.get_max() / r_rs.min(),
-    );
-
-    combinations.sort_unstable_by(|a, b| {
-        let i_a = k.get_typical_value() * a.vref.value;
-        let i_b = k.get_typical_value() * b.vref.value;
-        i_a.partial_cmp(&i_b).unwrap()
-    });
-    combinations.reverse();
+    let k = gain.div(&r_rs);
 
-    for x in combinations
+    let shortlist = combinations
         .iter()
         .filter(|&params| params.vref.max() <= constraint.voltage.max())
-        .filter(|&params| k.get_max() * params.vref.max() <= i_out)
-        .filter(|&params| params.r1.min() + params.r2.min() >= 10e3)
-        .filter(|&params| params.r1.max() + params.r2.max() <= 120e3)
-        .take(10)
-        .collect::<Vec<_>>()
-    {
-        let (r1, r1_prefix) = prefixed_for_resistance(x.r1.get_value());
-        println!("R1: {r1} {r1_prefix}Ω");
-        let (r2, r2_prefix) = prefixed_for_resistance(x.r2.get_value());
-        println!("R2: {r2} {r2_prefix}Ω");
-        println!("Vref: {}", x.vref.value);
-        println!("Vref Range: {}, {}", x.vref.min(), x.vref.max());
-        println!("Iout: {}", k.get_typical_value() * x.vref.value);
-        println!(
-            "Iout Range: {}, {}",
-            k.get_min() * x.vref.min(),
-            k.get_max() * x.vref.max()
+        .filter(|&params| output_current(&k, &params.vref).get_max() <= i_out)
+        .take(config.count)
+        .collect::<Vec<_>>();
+    if shortlist.is_empty() {
+        eprintln!(
+            "error: {} combinations found, but none keeps worst-case Vref within {} V \
+             and Iout within {} A",
+            combinations.len(),
+            constraint.voltage.max(),
+            i_out
         );
+        eprintln!("hint: use a tighter --tolerance or raise --vref-max");
+        std::process::exit(1);
+    }
+    for x in shortlist {
+        println!("{x}");
+        let current = output_current(&k, &x.vref);
+        println!("Iout: {}", current.get_typical_value());
+        println!("Iout Range: {}, {}", current.get_min(), current.get_max());
+        let current_typ_vref = output_current_at_typical_vref(&k, &x.vref);
         println!(
             "Iout Range (typ. Vref): {}, {}",
-            k.get_min() * x.vref.get_typical_value(),
-            k.get_max() * x.vref.get_typical_value()
+            current_typ_vref.get_min(),
+            current_typ_vref.get_max()
         );
         println!("----------");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocker(limit: impl FnOnce(&mut Constraint)) -> Blocker {
+        let mut constraint = Constraint::builder()
+            .voltage(Voltage::new_by_values(2.0, 1.9, 2.1))
+            .build()
+            .unwrap();
+        limit(&mut constraint);
+        let resistors = rc_param::get_series_resistor_list(Series::E24, 0.01);
+        find_blocker(
+            &constraint,
+            Voltage::new_by_allowance(5.0, 0.05),
+            &resistors,
+        )
+    }
+
+    #[test]
+    fn names_the_resistance_window() {
+        let blocker = blocker(|c| c.min_total_resistance = Ohms(1e9));
+        assert_eq!(blocker, Blocker::TotalResistance);
+    }
+
+    #[test]
+    fn names_the_power_limit() {
+        let blocker = blocker(|c| c.max_resistor_power = Some(Watts(1e-9)));
+        assert_eq!(blocker, Blocker::Power);
+    }
+
+    #[test]
+    fn names_the_current_limit_and_the_band() {
+        assert_eq!(blocker(|c| c.max_current = Amps(1e-9)), Blocker::Current);
+        let constraint = Constraint::builder()
+            .voltage(Voltage::new_by_values(4.99, 4.99, 4.99))
+            .build()
+            .unwrap();
+        let resistors = Resistor::from_values(&[1e3, 2e3], 0.01);
+        assert_eq!(
+            find_blocker(&constraint, Voltage::new_by_allowance(5.0, 0.0), &resistors),
+            Blocker::VrefBand
+        );
+    }
+}
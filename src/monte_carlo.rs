@@ -0,0 +1,200 @@
+use crate::divider::CircuitParameters;
+use crate::ranged::{RangedValue, Voltage};
+use crate::rc_param::PassiveComponent;
+
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn seed(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    // SplitMix64
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn standard_normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distribution {
+    Uniform,
+    // The tolerance band is taken as ±3σ and samples are clipped to it.
+    Gaussian,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MonteCarloOptions {
+    pub distribution: Distribution,
+    pub seed: u64,
+}
+
+impl Default for MonteCarloOptions {
+    fn default() -> Self {
+        Self {
+            distribution: Distribution::Gaussian,
+            seed: 0x5eed,
+        }
+    }
+}
+
+fn sample(rng: &mut Rng, distribution: Distribution, value: f64, min: f64, max: f64) -> f64 {
+    match distribution {
+        Distribution::Uniform => min + (max - min) * rng.next_f64(),
+        Distribution::Gaussian => {
+            let sigma = (max - min) / 6.0;
+            (value + sigma * rng.standard_normal()).clamp(min, max)
+        }
+    }
+}
+
+fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    sorted[((sorted.len() - 1) as f64 * fraction).round() as usize]
+}
+
+pub fn monte_carlo(
+    params: &CircuitParameters,
+    v_src: &Voltage,
+    samples: usize,
+) -> RangedValue<f64> {
+    monte_carlo_with(params, v_src, samples, &MonteCarloOptions::default())
+}
+
+fn sample_vrefs(
+    params: &CircuitParameters,
+    v_src: &Voltage,
+    samples: usize,
+    options: &MonteCarloOptions,
+) -> Vec<f64> {
+    let mut rng = Rng::seed(options.seed);
+    let d = options.distribution;
+    (0..samples.max(1))
+        .map(|_| {
+            let r1 = sample(
+                &mut rng,
+                d,
+                params.r1.get_value(),
+                params.r1.min(),
+                params.r1.max(),
+            );
+            let r2 = sample(
+                &mut rng,
+                d,
+                params.r2.get_value(),
+                params.r2.min(),
+                params.r2.max(),
+            );
+            let lower = match params.load {
+                Some(load) => {
+                    let load = sample(&mut rng, d, load.get_value(), load.min(), load.max());
+                    r2 * load / (r2 + load)
+                }
+                None => r2,
+            };
+            let v = sample(&mut rng, d, v_src.value, v_src.min(), v_src.max());
+            lower / (r1 + lower) * v
+        })
+        .collect::<Vec<f64>>()
+}
+
+pub fn monte_carlo_with(
+    params: &CircuitParameters,
+    v_src: &Voltage,
+    samples: usize,
+    options: &MonteCarloOptions,
+) -> RangedValue<f64> {
+    let mut vrefs = sample_vrefs(params, v_src, samples, options);
+    vrefs.sort_unstable_by(f64::total_cmp);
+    let mean = vrefs.iter().sum::<f64>() / vrefs.len() as f64;
+    RangedValue::new(mean, percentile(&vrefs, 0.01), percentile(&vrefs, 0.99))
+}
+
+// Fraction of samples with Vref inside [spec_min, spec_max].
+pub fn yield_estimate(
+    params: &CircuitParameters,
+    v_src: &Voltage,
+    spec_min: f64,
+    spec_max: f64,
+    samples: usize,
+) -> f64 {
+    let vrefs = sample_vrefs(params, v_src, samples, &MonteCarloOptions::default());
+    let passing = vrefs
+        .iter()
+        .filter(|v| (spec_min..=spec_max).contains(*v))
+        .count();
+    passing as f64 / vrefs.len() as f64
+}
+
+// Highest yield first; ties keep their existing order.
+pub fn rank_by_yield(
+    results: Vec<CircuitParameters>,
+    v_src: &Voltage,
+    spec_min: f64,
+    spec_max: f64,
+    samples: usize,
+) -> Vec<(CircuitParameters, f64)> {
+    let mut ranked = results
+        .into_iter()
+        .map(|params| {
+            let y = yield_estimate(&params, v_src, spec_min, spec_max, samples);
+            (params, y)
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::divider::{find_combinations, Constraint};
+    use crate::ranged::RangedType;
+    use crate::rc_param::Resistor;
+
+    // 10 kΩ over 10 kΩ with a 10 kΩ load across R2: 5 V · 5k / 15k.
+    fn loaded_pair() -> (CircuitParameters, Voltage) {
+        let v_src = Voltage::new_by_allowance(5.0, 0.0);
+        let constraint = Constraint::builder()
+            .voltage(Voltage::new_by_values(1.7, 0.0, 5.0))
+            .load(Resistor::new(10e3, 0.01))
+            .build()
+            .unwrap();
+        let resistors = Resistor::from_values(&[10e3], 0.01);
+        let params = find_combinations(constraint, v_src, resistors)
+            .unwrap()
+            .remove(0);
+        (params, v_src)
+    }
+
+    #[test]
+    fn samples_the_load() {
+        let (params, v_src) = loaded_pair();
+        assert!((params.vref.value - 5.0 / 3.0).abs() < 1e-9);
+        let mc = monte_carlo(&params, &v_src, 10_000);
+        assert!((mc.get_typical_value() - params.vref.value).abs() < 1e-3);
+        assert!(mc.get_min() >= params.vref.min() && mc.get_max() <= params.vref.max());
+    }
+
+    #[test]
+    fn yield_uses_the_loaded_vref() {
+        let (params, v_src) = loaded_pair();
+        // Unloaded, this pair would sit at 2.5 V and never pass.
+        assert_eq!(yield_estimate(&params, &v_src, 1.6, 1.75, 2_000), 1.0);
+        assert_eq!(yield_estimate(&params, &v_src, 2.4, 2.6, 2_000), 0.0);
+        let ranked = rank_by_yield(vec![params], &v_src, 1.6, 1.75, 2_000);
+        assert_eq!(ranked[0].1, 1.0);
+    }
+}
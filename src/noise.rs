@@ -0,0 +1,22 @@
+use crate::divider::CircuitParameters;
+use crate::rc_param::{Capacitor, PassiveComponent};
+
+const BOLTZMANN: f64 = 1.380_649e-23;
+
+// RMS Johnson noise of the Thevenin resistance, sqrt(4kTRΔf), in volts.
+// Weigh it against p_quiescent: raising the resistance lowers power but
+// raises noise by √R.
+pub fn noise_power_tradeoff(params: &CircuitParameters, bandwidth: f64, temperature: f64) -> f64 {
+    (4.0 * BOLTZMANN * temperature * params.r_thevenin.value.value() * bandwidth).sqrt()
+}
+
+// RMS noise at the filter cap, integrated over all frequencies. The Thevenin
+// resistance sets the noise bandwidth, π/2 · 1/(2πRC), and cancels out of
+// sqrt(4kTR · 1/(4RC)), leaving sqrt(kT/C).
+pub fn integrated_noise(
+    _params: &CircuitParameters,
+    c_filter: &Capacitor,
+    temperature: f64,
+) -> f64 {
+    (BOLTZMANN * temperature / c_filter.get_value()).sqrt()
+}
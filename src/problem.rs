@@ -0,0 +1,177 @@
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::divider::{find_best_combinations, Constraint};
+use crate::error::SolverError;
+use crate::ranged::Voltage;
+use crate::rc_param::{self, Series, Tolerance};
+use crate::units::{Amps, Ohms, Watts};
+
+// A complete request for the solver. Keys match the config file, any
+// missing key falls back to the Config default and unknown keys are errors.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(try_from = "ProblemSpec")]
+pub struct Problem {
+    pub vcc: Voltage,
+    pub target: Voltage,
+    pub max_current: f64,
+    pub max_resistor_power: Option<Watts>,
+    pub tolerance: f64,
+    pub series: Series,
+    pub min_total_resistance: f64,
+    pub max_total_resistance: f64,
+    pub exclude: Vec<f64>,
+    pub count: usize,
+}
+
+// The flat key layout of the JSON input, before the voltages are built.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ProblemSpec {
+    vcc: f64,
+    vcc_tolerance: f64,
+    vref: f64,
+    vref_min: f64,
+    vref_max: f64,
+    max_current: f64,
+    max_resistor_power: Option<Watts>,
+    tolerance: f64,
+    series: Series,
+    min_total_resistance: f64,
+    max_total_resistance: f64,
+    exclude: Vec<f64>,
+    count: usize,
+}
+
+impl Default for ProblemSpec {
+    fn default() -> Self {
+        let defaults = Config::default();
+        ProblemSpec {
+            vcc: defaults.vcc,
+            vcc_tolerance: defaults.vcc_tolerance,
+            vref: defaults.vref,
+            vref_min: defaults.vref_min,
+            vref_max: defaults.vref_max,
+            max_current: defaults.max_current,
+            max_resistor_power: None,
+            tolerance: defaults.tolerance,
+            series: defaults.series,
+            min_total_resistance: defaults.min_total_resistance,
+            max_total_resistance: defaults.max_total_resistance,
+            exclude: Vec::new(),
+            count: defaults.count,
+        }
+    }
+}
+
+// Held to the same rules as the command line and the config file.
+impl TryFrom<ProblemSpec> for Problem {
+    type Error = String;
+
+    fn try_from(spec: ProblemSpec) -> Result<Self, Self::Error> {
+        if !(spec.vcc.is_finite() && spec.vcc > 0.0) {
+            return Err("vcc must be a positive voltage".to_string());
+        }
+        Tolerance::try_fraction(spec.vcc_tolerance).map_err(|e| e.to_string())?;
+        Tolerance::try_fraction(spec.tolerance).map_err(|e| e.to_string())?;
+        if spec.count == 0 {
+            return Err("count must be at least 1".to_string());
+        }
+        Ok(Problem {
+            vcc: Voltage::new_by_allowance(spec.vcc, spec.vcc_tolerance),
+            target: Voltage::new_by_values(spec.vref, spec.vref_min, spec.vref_max),
+            max_current: spec.max_current,
+            max_resistor_power: spec.max_resistor_power,
+            tolerance: spec.tolerance,
+            series: spec.series,
+            min_total_resistance: spec.min_total_resistance,
+            max_total_resistance: spec.max_total_resistance,
+            exclude: spec.exclude,
+            count: spec.count,
+        })
+    }
+}
+
+// Parses a Problem, runs the solver and returns the best `count` results as
+// a JSON array.
+pub fn solve_from_json(input: &str) -> Result<String, SolverError> {
+    let problem: Problem = serde_json::from_str(input).map_err(|_| SolverError::InvalidProblem)?;
+    let mut builder = Constraint::builder()
+        .voltage(problem.target)
+        .max_current(Amps(problem.max_current))
+        .min_total_resistance(Ohms(problem.min_total_resistance))
+        .max_total_resistance(Ohms(problem.max_total_resistance));
+    if let Some(p_max) = problem.max_resistor_power {
+        builder = builder.max_resistor_power(p_max);
+    }
+    let constraint = builder.build().map_err(|_| SolverError::InvalidProblem)?;
+    let resistors = rc_param::exclude_values(
+        rc_param::get_series_resistor_list(problem.series, problem.tolerance),
+        &problem.exclude,
+    );
+    let results = find_best_combinations(constraint, problem.vcc, resistors, problem.count)?;
+    serde_json::to_string(&results).map_err(|_| SolverError::InvalidProblem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::divider::CircuitParameters;
+    use crate::rc_param::PassiveComponent;
+
+    #[test]
+    fn solves_with_defaults_and_round_trips() {
+        let output = solve_from_json(r#"{"count": 3}"#).unwrap();
+        let results: Vec<CircuitParameters> = serde_json::from_str(&output).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn applies_the_resistance_window() {
+        let output =
+            solve_from_json(r#"{"min_total_resistance": 50e3, "max_total_resistance": 60e3}"#)
+                .unwrap();
+        let results: Vec<CircuitParameters> = serde_json::from_str(&output).unwrap();
+        assert!(!results.is_empty());
+        assert!(results
+            .iter()
+            .all(|p| p.r1.min() + p.r2.min() >= 50e3 && p.r1.max() + p.r2.max() <= 60e3));
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_fractional_counts() {
+        assert_eq!(
+            solve_from_json(r#"{"max_curent": 1e-3}"#),
+            Err(SolverError::InvalidProblem)
+        );
+        assert_eq!(
+            solve_from_json(r#"{"count": 2.5}"#),
+            Err(SolverError::InvalidProblem)
+        );
+        assert!(solve_from_json(r#"{"count": 2}"#).is_ok());
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(
+            solve_from_json(r#"{"series": "\ud800A"}"#),
+            Err(SolverError::InvalidProblem)
+        );
+        assert_eq!(
+            solve_from_json(r#"{"series": "E7"}"#),
+            Err(SolverError::InvalidProblem)
+        );
+        for invalid in [
+            r#"{"tolerance": 1.5}"#,
+            r#"{"vcc_tolerance": 1.0}"#,
+            r#"{"vcc_tolerance": -3}"#,
+            r#"{"vcc": 0}"#,
+            r#"{"count": 0}"#,
+        ] {
+            assert_eq!(solve_from_json(invalid), Err(SolverError::InvalidProblem));
+        }
+        let deep = "[".repeat(100_000) + &"]".repeat(100_000);
+        assert_eq!(solve_from_json(&deep), Err(SolverError::InvalidProblem));
+    }
+}
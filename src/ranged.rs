@@ -0,0 +1,233 @@
+use crate::error::{RangeError, VoltageError};
+use crate::units::Volts;
+
+pub trait RangedType {
+    type Item;
+    fn get_typical_value(&self) -> Self::Item;
+    fn get_min(&self) -> Self::Item;
+    fn get_max(&self) -> Self::Item;
+
+    fn add<R>(&self, other: &R) -> RangedValue<f64>
+    where
+        Self: RangedType<Item = f64>,
+        R: RangedType<Item = f64>,
+    {
+        RangedValue::new(
+            self.get_typical_value() + other.get_typical_value(),
+            self.get_min() + other.get_min(),
+            self.get_max() + other.get_max(),
+        )
+    }
+
+    fn sub<R>(&self, other: &R) -> RangedValue<f64>
+    where
+        Self: RangedType<Item = f64>,
+        R: RangedType<Item = f64>,
+    {
+        RangedValue::new(
+            self.get_typical_value() - other.get_typical_value(),
+            self.get_min() - other.get_max(),
+            self.get_max() - other.get_min(),
+        )
+    }
+
+    fn mul<R>(&self, other: &R) -> RangedValue<f64>
+    where
+        Self: RangedType<Item = f64>,
+        R: RangedType<Item = f64>,
+    {
+        let corners = [
+            self.get_min() * other.get_min(),
+            self.get_min() * other.get_max(),
+            self.get_max() * other.get_min(),
+            self.get_max() * other.get_max(),
+        ];
+        RangedValue::new(
+            self.get_typical_value() * other.get_typical_value(),
+            corners.iter().copied().fold(f64::INFINITY, f64::min),
+            corners.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+
+    // For positive ranges this is min / other.max ..= max / other.min.
+    fn div<R>(&self, other: &R) -> RangedValue<f64>
+    where
+        Self: RangedType<Item = f64>,
+        R: RangedType<Item = f64>,
+    {
+        let typical = self.get_typical_value() / other.get_typical_value();
+        if other.get_min() <= 0.0 && other.get_max() >= 0.0 {
+            return RangedValue::new(typical, f64::NEG_INFINITY, f64::INFINITY);
+        }
+        let corners = [
+            self.get_min() / other.get_min(),
+            self.get_min() / other.get_max(),
+            self.get_max() / other.get_min(),
+            self.get_max() / other.get_max(),
+        ];
+        RangedValue::new(
+            typical,
+            corners.iter().copied().fold(f64::INFINITY, f64::min),
+            corners.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+}
+
+// Dyn-compatible view of any f64 range, so voltages, currents and gains can
+// sit together in a Vec<Box<dyn RangedF64>>. Implemented for every
+// RangedType<Item = f64>; the names differ to avoid clashing with its methods.
+pub trait RangedF64 {
+    fn typical(&self) -> f64;
+    fn lower(&self) -> f64;
+    fn upper(&self) -> f64;
+}
+
+impl<T> RangedF64 for T
+where
+    T: RangedType<Item = f64>,
+{
+    fn typical(&self) -> f64 {
+        self.get_typical_value()
+    }
+
+    fn lower(&self) -> f64 {
+        self.get_min()
+    }
+
+    fn upper(&self) -> f64 {
+        self.get_max()
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone)]
+pub struct Voltage {
+    pub value: f64,
+    min: f64,
+    max: f64,
+}
+
+impl RangedType for Voltage {
+    type Item = f64;
+
+    fn get_typical_value(&self) -> Self::Item {
+        self.value
+    }
+
+    fn get_min(&self) -> Self::Item {
+        self.min
+    }
+
+    fn get_max(&self) -> Self::Item {
+        self.max
+    }
+}
+
+impl Voltage {
+    pub fn new_by_allowance(value: f64, allowance: f64) -> Self {
+        Self {
+            value,
+            min: value * (1.0 - allowance),
+            max: value * (1.0 + allowance),
+        }
+    }
+
+    pub fn new_by_values(value: f64, min: f64, max: f64) -> Self {
+        Self { value, min, max }
+    }
+
+    pub fn try_new_by_values(value: f64, min: f64, max: f64) -> Result<Self, VoltageError> {
+        if !(value.is_finite() && min.is_finite() && max.is_finite()) {
+            return Err(VoltageError::NonFiniteValue);
+        }
+        if min > value || value > max {
+            return Err(VoltageError::InvalidRange);
+        }
+        Ok(Self::new_by_values(value, min, max))
+    }
+
+    pub fn volts(&self) -> Volts {
+        Volts(self.value)
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug)]
+pub struct RangedValue<T>
+where
+    T: Copy,
+{
+    pub value: T,
+    min: T,
+    max: T,
+}
+
+impl<T> RangedType for RangedValue<T>
+where
+    T: Copy,
+{
+    type Item = T;
+
+    fn get_typical_value(&self) -> Self::Item {
+        self.value
+    }
+
+    fn get_min(&self) -> Self::Item {
+        self.min
+    }
+
+    fn get_max(&self) -> Self::Item {
+        self.max
+    }
+}
+
+impl<T> RangedValue<T>
+where
+    T: Copy,
+{
+    pub fn new(
+        value: <Self as RangedType>::Item,
+        min: <Self as RangedType>::Item,
+        max: <Self as RangedType>::Item,
+    ) -> Self {
+        Self { value, min, max }
+    }
+}
+
+impl<T> RangedValue<T>
+where
+    T: Copy + PartialOrd,
+{
+    pub fn try_new(value: T, min: T, max: T) -> Result<Self, RangeError> {
+        // Written so that NaN fails every comparison and is rejected.
+        if min <= value && value <= max {
+            Ok(Self::new(value, min, max))
+        } else {
+            Err(RangeError)
+        }
+    }
+}
+
+pub type Gain = RangedValue<f64>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn div_takes_the_outer_corners() {
+        let a = RangedValue::new(1.5, 1.0, 2.0);
+        let b = RangedValue::new(4.5, 4.0, 5.0);
+        let quotient = a.div(&b);
+        assert_eq!(quotient.get_typical_value(), 1.5 / 4.5);
+        assert_eq!(quotient.get_min(), 0.2);
+        assert_eq!(quotient.get_max(), 0.5);
+    }
+}
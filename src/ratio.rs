@@ -0,0 +1,133 @@
+use crate::error::SolverError;
+use crate::rc_param::{self, Network, PassiveComponent, Resistor};
+
+// Networks hold at most two parts per arm, so the search stops at four resistors.
+const MAX_TOTAL_COMPONENTS: usize = 4;
+
+// ratio = R2 / (R1 + R2)
+#[derive(Debug)]
+pub struct MinimumNetwork {
+    pub r1: Network,
+    pub r2: Network,
+    pub components: usize,
+    pub ratio: f64,
+    pub ratio_error: f64,
+}
+
+pub fn find_minimum_network(
+    target_ratio: f64,
+    resistors: Vec<Resistor>,
+    max_error: f64,
+    max_components: usize,
+) -> Result<MinimumNetwork, SolverError> {
+    if resistors.is_empty() {
+        return Err(SolverError::EmptyResistorList);
+    }
+    if !target_ratio.is_finite()
+        || !max_error.is_finite()
+        || !resistors.iter().all(|r| r.get_value().is_finite())
+    {
+        return Err(SolverError::NonFiniteValue);
+    }
+    if target_ratio <= 0.0 || target_ratio >= 1.0 {
+        return Err(SolverError::NoCombinationFound);
+    }
+
+    let resistors = rc_param::without_zero_values(resistors);
+    let mut by_size: [Vec<(f64, Network)>; 2] = [Vec::new(), Vec::new()];
+    for network in rc_param::expand_networks(&resistors, 2) {
+        let size = network.parts().len();
+        by_size[size - 1].push((network.equivalent().get_value(), network));
+    }
+    for arm in by_size.iter_mut() {
+        arm.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+
+    for components in 2..=max_components.min(MAX_TOTAL_COMPONENTS) {
+        let mut best: Option<MinimumNetwork> = None;
+        for r1_size in 1..=2 {
+            let r2_size = components - r1_size;
+            if !(1..=2).contains(&r2_size) {
+                continue;
+            }
+            for (r1_value, r1) in &by_size[r1_size - 1] {
+                let ideal = r1_value * target_ratio / (1.0 - target_ratio);
+                let Some((ratio, r2)) =
+                    nearest_r2(&by_size[r2_size - 1], ideal, *r1_value, target_ratio)
+                else {
+                    continue;
+                };
+                let ratio_error = ratio - target_ratio;
+                let better = match &best {
+                    None => true,
+                    Some(b) => ratio_error.abs() < b.ratio_error.abs(),
+                };
+                if better {
+                    best = Some(MinimumNetwork {
+                        r1: *r1,
+                        r2: *r2,
+                        components,
+                        ratio,
+                        ratio_error,
+                    });
+                }
+            }
+        }
+        if let Some(best) = best {
+            if best.ratio_error.abs() <= max_error {
+                return Ok(best);
+            }
+        }
+    }
+    Err(SolverError::NoCombinationFound)
+}
+
+// The arm is sorted by value; only the neighbours of the ideal R2 can be closest.
+fn nearest_r2(
+    arm: &[(f64, Network)],
+    ideal: f64,
+    r1_value: f64,
+    target_ratio: f64,
+) -> Option<(f64, &Network)> {
+    let i = arm.partition_point(|(v, _)| *v < ideal);
+    [i.checked_sub(1), Some(i)]
+        .into_iter()
+        .flatten()
+        .filter_map(|j| arm.get(j))
+        .map(|(v, n)| (v / (r1_value + v), n))
+        .min_by(|a, b| {
+            (a.0 - target_ratio)
+                .abs()
+                .total_cmp(&(b.0 - target_ratio).abs())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rc_param::small_test_resistors;
+
+    #[test]
+    fn adds_a_part_only_when_pairs_miss() {
+        // 3k over 2k is exactly 0.4.
+        let pair = find_minimum_network(0.4, small_test_resistors(), 1e-9, 4).unwrap();
+        assert_eq!(pair.components, 2);
+        assert!(pair.ratio_error.abs() < 1e-12);
+
+        // No pair gets below 1k / (1k + 3k) = 0.25, but one network of two
+        // parts reaches 0.2, e.g. 4k over 1k or 2k over 500 Ω.
+        let triple = find_minimum_network(0.2, small_test_resistors(), 1e-9, 4).unwrap();
+        assert_eq!(triple.components, 3);
+        assert!(triple.ratio_error.abs() < 1e-12);
+        let (r1, r2) = (
+            triple.r1.equivalent().get_value(),
+            triple.r2.equivalent().get_value(),
+        );
+        assert!((r2 / (r1 + r2) - 0.2).abs() < 1e-12);
+
+        assert_eq!(
+            find_minimum_network(0.2, small_test_resistors(), 1e-9, 2).err(),
+            Some(SolverError::NoCombinationFound)
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use std::f64::consts::PI;
+
+use crate::divider::CircuitParameters;
+use crate::ranged::{RangedType, RangedValue};
+use crate::rc_param::{Capacitor, PassiveComponent, Resistor};
+use crate::units::Ohms;
+
+pub fn rc_cutoff(r: &Resistor, c: &Capacitor) -> RangedValue<f64> {
+    let cutoff = |r: f64, c: f64| 1.0 / (2.0 * PI * r * c);
+    RangedValue::new(
+        cutoff(r.get_value(), c.get_value()),
+        cutoff(r.max(), c.max()),
+        cutoff(r.min(), c.min()),
+    )
+}
+
+// |R1 ∥ R2 ∥ (ESR + 1/jωC)| with the bypass capacitor across R2.
+pub fn output_impedance_at(r1: &Resistor, r2: &Resistor, c: &Capacitor, freq: f64) -> f64 {
+    let r_thevenin = r1.parallel(r2).get_value();
+    let reactance = 1.0 / (2.0 * PI * freq * c.get_value());
+    let esr = c.get_esr();
+    r_thevenin * esr.hypot(reactance) / (r_thevenin + esr).hypot(reactance)
+}
+
+// n_tau time constants of (R1 ∥ R2 + ESR) · C_load; about 4.6 τ settles to
+// 1%. The ESR is in series with the cap, so it adds to the charging path.
+pub fn settling_time(
+    params: &CircuitParameters,
+    c_load: &Capacitor,
+    n_tau: f64,
+) -> RangedValue<f64> {
+    let r = params.r_thevenin;
+    let esr = c_load.get_esr();
+    RangedValue::new(
+        n_tau * (r.get_typical_value().value() + esr) * c_load.get_value(),
+        n_tau * (r.get_min().value() + esr) * c_load.min(),
+        n_tau * (r.get_max().value() + esr) * c_load.max(),
+    )
+}
+
+// Fraction of supply ripple reaching the tap. Unbypassed this is just the
+// ratio, including any load.
+pub fn psrr(params: &CircuitParameters) -> RangedValue<f64> {
+    params.ratio()
+}
+
+// With a bypass capacitor across R2 the ratio rolls off by
+// |ESR + 1/jωC| / |R_th + ESR + 1/jωC|, flattening at the ESR divider.
+pub fn psrr_at(params: &CircuitParameters, c: &Capacitor, freq: f64) -> RangedValue<f64> {
+    let ratio = params.ratio();
+    let esr = c.get_esr();
+    let rolloff = |r_thevenin: Ohms, c: f64| {
+        let reactance = 1.0 / (2.0 * PI * freq * c);
+        esr.hypot(reactance) / (r_thevenin.value() + esr).hypot(reactance)
+    };
+    let r = params.r_thevenin;
+    RangedValue::new(
+        ratio.get_typical_value() * rolloff(r.get_typical_value(), c.get_value()),
+        ratio.get_min() * rolloff(r.get_max(), c.max()),
+        ratio.get_max() * rolloff(r.get_min(), c.min()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::divider::{find_combinations, Constraint};
+    use crate::ranged::Voltage;
+    use crate::rc_param::small_test_resistors;
+
+    #[test]
+    fn settling_time_includes_the_esr() {
+        // 1k over 1k halves 3 V with R_th = 500 Ω.
+        let constraint = Constraint::builder()
+            .voltage(Voltage::new_by_values(1.5, 1.45, 1.55))
+            .build()
+            .unwrap();
+        let v_src = Voltage::new_by_allowance(3.0, 0.0);
+        let results = find_combinations(constraint, v_src, small_test_resistors()).unwrap();
+        let params = results
+            .iter()
+            .find(|p| p.r1.get_value() == 1e3 && p.r2.get_value() == 1e3)
+            .unwrap();
+
+        // (500 Ω + 100 Ω) · 1 µF.
+        let c = Capacitor::new(1e-6, 0.0).with_esr(100.0);
+        let t = settling_time(params, &c, 1.0);
+        assert!((t.get_typical_value() - 600e-6).abs() < 1e-12);
+        let bare = settling_time(params, &Capacitor::new(1e-6, 0.0), 1.0);
+        assert!((bare.get_typical_value() - 500e-6).abs() < 1e-12);
+    }
+}
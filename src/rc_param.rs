@@ -1,42 +1,239 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::ops::Range;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use crate::error::{ParseSeriesError, ToleranceError};
+use crate::units::Ohms;
 
 const E24: [f64; 24] = [
     1.0, 1.1, 1.2, 1.3, 1.5, 1.6, 1.8, 2.0, 2.2, 2.4, 2.7, 3.0, 3.3, 3.6, 3.9, 4.3, 4.7, 5.1, 5.6,
     6.2, 6.8, 7.5, 8.2, 9.1,
 ];
 
-#[allow(dead_code)]
-#[repr(usize)]
-enum Series {
-    E24 = 1,
-    E12 = 2,
-    E6 = 4,
-    E3 = 8,
+const E192: [f64; 192] = [
+    1.00, 1.01, 1.02, 1.04, 1.05, 1.06, 1.07, 1.09, 1.10, 1.11, 1.13, 1.14, 1.15, 1.17, 1.18, 1.20,
+    1.21, 1.23, 1.24, 1.26, 1.27, 1.29, 1.30, 1.32, 1.33, 1.35, 1.37, 1.38, 1.40, 1.42, 1.43, 1.45,
+    1.47, 1.49, 1.50, 1.52, 1.54, 1.56, 1.58, 1.60, 1.62, 1.64, 1.65, 1.67, 1.69, 1.72, 1.74, 1.76,
+    1.78, 1.80, 1.82, 1.84, 1.87, 1.89, 1.91, 1.93, 1.96, 1.98, 2.00, 2.03, 2.05, 2.08, 2.10, 2.13,
+    2.15, 2.18, 2.21, 2.23, 2.26, 2.29, 2.32, 2.34, 2.37, 2.40, 2.43, 2.46, 2.49, 2.52, 2.55, 2.58,
+    2.61, 2.64, 2.67, 2.71, 2.74, 2.77, 2.80, 2.84, 2.87, 2.91, 2.94, 2.98, 3.01, 3.05, 3.09, 3.12,
+    3.16, 3.20, 3.24, 3.28, 3.32, 3.36, 3.40, 3.44, 3.48, 3.52, 3.57, 3.61, 3.65, 3.70, 3.74, 3.79,
+    3.83, 3.88, 3.92, 3.97, 4.02, 4.07, 4.12, 4.17, 4.22, 4.27, 4.32, 4.37, 4.42, 4.48, 4.53, 4.59,
+    4.64, 4.70, 4.75, 4.81, 4.87, 4.93, 4.99, 5.05, 5.11, 5.17, 5.23, 5.30, 5.36, 5.42, 5.49, 5.56,
+    5.62, 5.69, 5.76, 5.83, 5.90, 5.97, 6.04, 6.12, 6.19, 6.26, 6.34, 6.42, 6.49, 6.57, 6.65, 6.73,
+    6.81, 6.90, 6.98, 7.06, 7.15, 7.23, 7.32, 7.41, 7.50, 7.59, 7.68, 7.77, 7.87, 7.96, 8.06, 8.16,
+    8.25, 8.35, 8.45, 8.56, 8.66, 8.76, 8.87, 8.98, 9.09, 9.20, 9.31, 9.42, 9.53, 9.65, 9.76, 9.88,
+];
+
+const DEFAULT_POWER_RATING: f64 = 0.0625;
+const DEFAULT_TEMPCO_PPM: f64 = 100.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Series {
+    E192,
+    E96,
+    E48,
+    E24,
+    E12,
+    E6,
+    E3,
+}
+
+impl Series {
+    pub fn from_name(name: &str) -> Option<Series> {
+        match name.to_ascii_uppercase().as_str() {
+            "E3" => Some(Series::E3),
+            "E6" => Some(Series::E6),
+            "E12" => Some(Series::E12),
+            "E24" => Some(Series::E24),
+            "E48" => Some(Series::E48),
+            "E96" => Some(Series::E96),
+            "E192" => Some(Series::E192),
+            _ => None,
+        }
+    }
+
+    // Conventional tolerance class for parts sold in this series.
+    pub fn default_tolerance(&self) -> f64 {
+        match self {
+            Series::E192 => 0.005,
+            Series::E96 => 0.01,
+            Series::E48 => 0.02,
+            Series::E24 => 0.05,
+            Series::E12 => 0.10,
+            Series::E6 => 0.20,
+            Series::E3 => 0.20,
+        }
+    }
+
+    // True if the value is a decade multiple of one of this series' values.
+    pub fn contains(&self, value: f64) -> bool {
+        if !(value.is_finite() && value > 0.0) {
+            return false;
+        }
+        let normalized = value / 10f64.powi(value.log10().floor() as i32);
+        get_e_series_values(*self).iter().any(|v| {
+            (normalized - v).abs() <= 1e-6 * v || (normalized / 10.0 - v).abs() <= 1e-6 * v
+        })
+    }
+}
+
+impl FromStr for Series {
+    type Err = ParseSeriesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Series::from_name(s).ok_or_else(|| ParseSeriesError(s.to_string()))
+    }
+}
+
+impl fmt::Display for Series {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Series::E192 => "E192",
+            Series::E96 => "E96",
+            Series::E48 => "E48",
+            Series::E24 => "E24",
+            Series::E12 => "E12",
+            Series::E6 => "E6",
+            Series::E3 => "E3",
+        };
+        f.write_str(name)
+    }
+}
+
+// Serialized by name, so the same strings work in config files and JSON.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Series {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Series {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
 }
 
-fn get_e_series_values(series: Series) -> Vec<f64> {
-    E24.iter()
-        .step_by(series as usize)
-        .copied()
-        .collect::<Vec<f64>>()
+// Mantissas of one decade, from 1.0 up to below 10.
+pub fn get_e_series_values(series: Series) -> Vec<f64> {
+    let (table, step): (&[f64], usize) = match series {
+        Series::E192 => (&E192, 1),
+        Series::E96 => (&E192, 2),
+        Series::E48 => (&E192, 4),
+        Series::E24 => (&E24, 1),
+        Series::E12 => (&E24, 2),
+        Series::E6 => (&E24, 4),
+        Series::E3 => (&E24, 8),
+    };
+    table.iter().step_by(step).copied().collect::<Vec<f64>>()
 }
 
+// Stored as a fraction: 0.01 is 1 %. Use the constructors to say which
+// convention a number is in.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Tolerance(f64);
+
+impl Tolerance {
+    pub fn fraction(fraction: f64) -> Self {
+        Tolerance(fraction)
+    }
+
+    // A tolerance of 1 or more would push min() to zero or below.
+    pub fn try_fraction(fraction: f64) -> Result<Self, ToleranceError> {
+        if (0.0..1.0).contains(&fraction) {
+            Ok(Tolerance(fraction))
+        } else {
+            Err(ToleranceError(fraction))
+        }
+    }
+
+    pub fn percent(percent: f64) -> Self {
+        Tolerance(percent / 100.0)
+    }
+
+    pub fn as_fraction(&self) -> f64 {
+        self.0
+    }
+
+    pub fn as_percent(&self) -> f64 {
+        self.0 * 100.0
+    }
+}
+
+impl From<Tolerance> for f64 {
+    fn from(tolerance: Tolerance) -> f64 {
+        tolerance.0
+    }
+}
+
+// All raw f64 tolerances in this trait are fractions, not percent.
 pub trait PassiveComponent {
     fn get_value(&self) -> f64;
     fn get_tolerance(&self) -> f64;
+    fn get_tolerance_plus(&self) -> f64 {
+        self.get_tolerance()
+    }
+    fn get_tolerance_minus(&self) -> f64 {
+        self.get_tolerance()
+    }
     fn max(&self) -> f64 {
-        self.get_value() * (1.0 + self.get_tolerance())
+        self.get_value() * (1.0 + self.get_tolerance_plus())
     }
     fn min(&self) -> f64 {
-        self.get_value() * (1.0 - self.get_tolerance())
+        self.get_value() * (1.0 - self.get_tolerance_minus())
     }
     fn new(value: f64, tolerance: f64) -> Self;
+    fn try_new(value: f64, tolerance: f64) -> Result<Self, ToleranceError>
+    where
+        Self: Sized,
+    {
+        Tolerance::try_fraction(tolerance).map(|t| Self::new(value, t.as_fraction()))
+    }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug)]
 pub struct Resistor {
     value: f64,
-    tolerance: f64,
+    tolerance_plus: f64,
+    tolerance_minus: f64,
+    power_rating: f64,
+    tempco_ppm: f64,
+}
+
+// Floats are compared after quantizing to fixed point (µΩ, ppm, µW and
+// 0.001 ppm/K), so values that differ only by rounding noise from list
+// generation, like 4700 and 4700.000000000001, compare and hash equal.
+impl Resistor {
+    fn key(&self) -> [i64; 5] {
+        [
+            (self.value * 1e6).round() as i64,
+            (self.tolerance_plus * 1e6).round() as i64,
+            (self.tolerance_minus * 1e6).round() as i64,
+            (self.power_rating * 1e6).round() as i64,
+            (self.tempco_ppm * 1e3).round() as i64,
+        ]
+    }
+}
+
+impl PartialEq for Resistor {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Resistor {}
+
+impl Hash for Resistor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
 }
 
 impl PassiveComponent for Resistor {
@@ -45,11 +242,19 @@ impl PassiveComponent for Resistor {
     }
 
     fn get_tolerance(&self) -> f64 {
-        self.tolerance
+        self.tolerance_plus.max(self.tolerance_minus)
+    }
+
+    fn get_tolerance_plus(&self) -> f64 {
+        self.tolerance_plus
+    }
+
+    fn get_tolerance_minus(&self) -> f64 {
+        self.tolerance_minus
     }
 
     fn new(value: f64, tolerance: f64) -> Self {
-        Resistor { value, tolerance }
+        Self::with_rating(value, tolerance, DEFAULT_POWER_RATING)
     }
 }
 
@@ -58,12 +263,229 @@ impl Resistor {
     fn new_from_value(value: f64) -> Self {
         Self::new(value, 0.05)
     }
+
+    /// Builds a resistor list from arbitrary values, e.g. parts on hand.
+    ///
+    /// ```
+    /// use voltage_div::{find_combinations, Constraint, Resistor, Voltage};
+    ///
+    /// let bom = Resistor::from_values(&[1.0e3, 2.2e3, 4.7e3, 10.0e3, 33.0e3], 0.01);
+    /// let constraint = Constraint::builder()
+    ///     .voltage(Voltage::new_by_values(1.65, 1.5, 1.8))
+    ///     .build()
+    ///     .unwrap();
+    /// let results = find_combinations(constraint, Voltage::new_by_allowance(3.3, 0.05), bom).unwrap();
+    /// assert!(!results.is_empty());
+    /// ```
+    pub fn from_values(values: &[f64], tolerance: f64) -> Vec<Resistor> {
+        values
+            .iter()
+            .map(|&v| Resistor::new(v, tolerance))
+            .collect()
+    }
+
+    pub fn from_percent_tolerance(value: f64, percent: f64) -> Self {
+        Self::with_tolerance(value, Tolerance::percent(percent))
+    }
+
+    pub fn with_tolerance(value: f64, tolerance: Tolerance) -> Self {
+        Self::new(value, tolerance.as_fraction())
+    }
+
+    pub fn with_rating(value: f64, tolerance: f64, power_rating: f64) -> Self {
+        Resistor {
+            value,
+            tolerance_plus: tolerance,
+            tolerance_minus: tolerance,
+            power_rating,
+            tempco_ppm: DEFAULT_TEMPCO_PPM,
+        }
+    }
+
+    pub fn with_asymmetric_tolerance(
+        value: f64,
+        tolerance_plus: f64,
+        tolerance_minus: f64,
+    ) -> Self {
+        Resistor {
+            value,
+            tolerance_plus,
+            tolerance_minus,
+            power_rating: DEFAULT_POWER_RATING,
+            tempco_ppm: DEFAULT_TEMPCO_PPM,
+        }
+    }
+
+    pub fn try_with_asymmetric_tolerance(
+        value: f64,
+        tolerance_plus: f64,
+        tolerance_minus: f64,
+    ) -> Result<Self, ToleranceError> {
+        Tolerance::try_fraction(tolerance_plus)?;
+        Tolerance::try_fraction(tolerance_minus)?;
+        Ok(Self::with_asymmetric_tolerance(
+            value,
+            tolerance_plus,
+            tolerance_minus,
+        ))
+    }
+
+    pub fn with_power_rating(self, power_rating: f64) -> Self {
+        Resistor {
+            power_rating,
+            ..self
+        }
+    }
+
+    pub fn with_tempco(self, tempco_ppm: f64) -> Self {
+        Resistor { tempco_ppm, ..self }
+    }
+
+    pub fn resistance(&self) -> Ohms {
+        Ohms(self.value)
+    }
+
+    pub fn scaled(self, factor: f64) -> Self {
+        Resistor {
+            value: self.value * factor,
+            ..self
+        }
+    }
+
+    fn from_bounds(value: f64, min: f64, max: f64, power_rating: f64, tempco_ppm: f64) -> Self {
+        Resistor {
+            value,
+            tolerance_plus: (max - value) / value,
+            tolerance_minus: (value - min) / value,
+            power_rating,
+            tempco_ppm,
+        }
+    }
+
+    pub fn get_tempco_ppm(&self) -> f64 {
+        self.tempco_ppm
+    }
+
+    pub fn value_at(&self, delta_celsius: f64) -> f64 {
+        self.value * (1.0 + self.tempco_ppm * 1e-6 * delta_celsius)
+    }
+
+    pub fn get_power_rating(&self) -> f64 {
+        self.power_rating
+    }
+
+    pub fn series(&self, other: &Resistor) -> Resistor {
+        let value = self.value + other.value;
+        let min = self.min() + other.min();
+        let max = self.max() + other.max();
+        let power_rating = f64::min(
+            self.power_rating * value / self.value,
+            other.power_rating * value / other.value,
+        );
+        let tempco_ppm = (self.tempco_ppm * self.value + other.tempco_ppm * other.value) / value;
+        Self::from_bounds(value, min, max, power_rating, tempco_ppm)
+    }
+
+    pub fn parallel(&self, other: &Resistor) -> Resistor {
+        let combine = |a: f64, b: f64| a * b / (a + b);
+        let value = combine(self.value, other.value);
+        let min = combine(self.min(), other.min());
+        let max = combine(self.max(), other.max());
+        let power_rating = f64::min(
+            self.power_rating * self.value / value,
+            other.power_rating * other.value / value,
+        );
+        let tempco_ppm = (self.tempco_ppm * other.value + other.tempco_ppm * self.value)
+            / (self.value + other.value);
+        Self::from_bounds(value, min, max, power_rating, tempco_ppm)
+    }
+}
+
+// Temperature coefficient of R2 / (R1 + R2) in ppm/°C; matched tempcos cancel.
+pub fn ratio_tempco(r1: &Resistor, r2: &Resistor) -> f64 {
+    let ratio = r2.value / (r1.value + r2.value);
+    (1.0 - ratio) * (r2.tempco_ppm - r1.tempco_ppm)
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Network {
+    Single(Resistor),
+    Series(Resistor, Resistor),
+    Parallel(Resistor, Resistor),
+}
+
+impl Network {
+    pub fn equivalent(&self) -> Resistor {
+        match self {
+            Network::Single(r) => *r,
+            Network::Series(a, b) => a.series(b),
+            Network::Parallel(a, b) => a.parallel(b),
+        }
+    }
+
+    pub fn parts(&self) -> Vec<Resistor> {
+        match self {
+            Network::Single(r) => vec![*r],
+            Network::Series(a, b) | Network::Parallel(a, b) => vec![*a, *b],
+        }
+    }
+}
+
+pub fn filter_resistors(
+    resistors: Vec<Resistor>,
+    keep: impl Fn(&Resistor) -> bool,
+) -> Vec<Resistor> {
+    resistors.into_iter().filter(|r| keep(r)).collect()
+}
+
+// Values are matched up to floating-point noise, so 4.7e3 also removes the
+// list's 4700.000000000001.
+pub fn exclude_values(resistors: Vec<Resistor>, exclude: &[f64]) -> Vec<Resistor> {
+    filter_resistors(resistors, |r| {
+        !exclude
+            .iter()
+            .any(|v| (r.get_value() - v).abs() <= 1e-9 * v.abs())
+    })
+}
+
+// Zero-value parts (jumpers on a BOM) can never form a divider and would make
+// every ratio involving them 0/0.
+pub(crate) fn without_zero_values<C: PassiveComponent>(components: Vec<C>) -> Vec<C> {
+    components
+        .into_iter()
+        .filter(|c| c.get_value() > 0.0)
+        .collect()
+}
+
+// Preserves every ratio in the list while moving it to a new impedance.
+pub fn scale_resistor_list(resistors: &[Resistor], factor: f64) -> Vec<Resistor> {
+    resistors.iter().map(|r| r.scaled(factor)).collect()
+}
+
+// Networks of more than two parts are not generated; counts above 2 behave like 2.
+pub fn expand_networks(resistors: &[Resistor], max_components: usize) -> Vec<Network> {
+    let mut networks = resistors
+        .iter()
+        .map(|r| Network::Single(*r))
+        .collect::<Vec<Network>>();
+    if max_components >= 2 {
+        for (i, a) in resistors.iter().enumerate() {
+            for b in &resistors[i..] {
+                networks.push(Network::Series(*a, *b));
+                networks.push(Network::Parallel(*a, *b));
+            }
+        }
+    }
+    networks
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug)]
 pub struct Capacitor {
     value: f64,
     tolerance: f64,
+    esr: f64,
 }
 
 impl PassiveComponent for Capacitor {
@@ -74,7 +496,11 @@ impl PassiveComponent for Capacitor {
         self.tolerance
     }
     fn new(value: f64, tolerance: f64) -> Self {
-        Capacitor { value, tolerance }
+        Capacitor {
+            value,
+            tolerance,
+            esr: 0.0,
+        }
     }
 }
 
@@ -83,48 +509,295 @@ impl Capacitor {
     fn new_from_value(value: f64) -> Self {
         Self::new(value, 0.20)
     }
+
+    pub fn with_esr(self, esr: f64) -> Self {
+        Capacitor { esr, ..self }
+    }
+
+    pub fn get_esr(&self) -> f64 {
+        self.esr
+    }
+
+    pub fn impedance_at(&self, freq: f64) -> f64 {
+        let reactance = 1.0 / (2.0 * std::f64::consts::PI * freq * self.value);
+        self.esr.hypot(reactance)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Inductor {
+    value: f64,
+    tolerance: f64,
+}
+
+impl PassiveComponent for Inductor {
+    fn get_value(&self) -> f64 {
+        self.value
+    }
+    fn get_tolerance(&self) -> f64 {
+        self.tolerance
+    }
+    fn new(value: f64, tolerance: f64) -> Self {
+        Inductor { value, tolerance }
+    }
+}
+
+// The track is modelled as two legs, wiper to end A and wiper to end B.
+// Both legs share the track tolerance.
+#[derive(Clone, Copy, Debug)]
+pub struct Potentiometer {
+    value: f64,
+    tolerance: f64,
+    wiper: f64,
+}
+
+impl PassiveComponent for Potentiometer {
+    fn get_value(&self) -> f64 {
+        self.value
+    }
+    fn get_tolerance(&self) -> f64 {
+        self.tolerance
+    }
+    fn new(value: f64, tolerance: f64) -> Self {
+        Potentiometer {
+            value,
+            tolerance,
+            wiper: 0.5,
+        }
+    }
+}
+
+impl Potentiometer {
+    pub fn with_wiper(self, wiper: f64) -> Self {
+        Potentiometer {
+            wiper: wiper.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    pub fn get_wiper(&self) -> f64 {
+        self.wiper
+    }
+
+    pub fn leg_resistance(&self, wiper: f64) -> (Resistor, Resistor) {
+        let wiper = wiper.clamp(0.0, 1.0);
+        (
+            Resistor::new(self.value * wiper, self.tolerance),
+            Resistor::new(self.value * (1.0 - wiper), self.tolerance),
+        )
+    }
+
+    pub fn legs(&self) -> (Resistor, Resistor) {
+        self.leg_resistance(self.wiper)
+    }
 }
 
 #[allow(dead_code)]
 pub fn get_capacitor_list(tolerance: f64) -> Vec<Capacitor> {
-    let expressions_for_e6 = 1u8..6u8;
-    let expressions_for_e3 = 6u8..9u8;
-    let e6 = get_e_series_values(Series::E6);
-    let e3 = get_e_series_values(Series::E3);
-
-    let value_to_pico = |v: f64, exp: i32| -> f64 { v * 10f64.powi(exp - 12) };
-
-    let generate_values_list =
-        |expressions: Range<u8>, series_values: Vec<f64>| -> Vec<Capacitor> {
-            expressions
-                .flat_map(|exp| {
-                    series_values
-                        .iter()
-                        .map(|v| Capacitor::new(value_to_pico(*v, exp as i32), tolerance))
-                        .collect::<Vec<Capacitor>>()
-                })
-                .collect()
-        };
+    // E6 from 10 pF to 1 µF, E3 above that
+    [
+        get_capacitor_list_for(Series::E6, -11..-6, tolerance),
+        get_capacitor_list_for(Series::E3, -6..-3, tolerance),
+    ]
+    .concat()
+}
 
-    let values_e6: Vec<Capacitor> = generate_values_list(expressions_for_e6, e6);
-    let values_e3: Vec<Capacitor> = generate_values_list(expressions_for_e3, e3);
-    vec![values_e6, values_e3]
-        .iter()
-        .flatten()
-        .cloned()
-        .collect::<Vec<Capacitor>>()
+// Decades are powers of ten in farads, so -7..-5 spans 100 nF to 8.2 µF.
+pub fn get_capacitor_list_for(
+    series: Series,
+    decades: Range<i32>,
+    tolerance: f64,
+) -> Vec<Capacitor> {
+    let values = get_e_series_values(series);
+
+    decades
+        .flat_map(|exp| {
+            values
+                .iter()
+                .map(|v| Capacitor::new(scale_to_decade(*v, exp), tolerance))
+                .collect::<Vec<Capacitor>>()
+        })
+        .collect()
+}
+
+fn scale_to_decade(value: f64, exp: i32) -> f64 {
+    if exp < 0 {
+        value / 10.0f64.powi(-exp)
+    } else {
+        value * 10.0f64.powi(exp)
+    }
+}
+
+pub fn nearest_e_series(value: f64, series: Series) -> Resistor {
+    let values = get_e_series_values(series);
+    let decade = if value > 0.0 {
+        value.log10().floor() as i32
+    } else {
+        0
+    };
+    let nearest = (decade - 1..=decade + 1)
+        .flat_map(|exp| values.iter().map(move |v| scale_to_decade(*v, exp)))
+        .fold(f64::NAN, |best, candidate| {
+            let (d_best, d_candidate) = ((best - value).abs(), (candidate - value).abs());
+            if best.is_nan() || d_candidate < d_best || (d_candidate == d_best && candidate < best)
+            {
+                candidate
+            } else {
+                best
+            }
+        });
+    Resistor::new(nearest, series.default_tolerance())
 }
 
 #[allow(dead_code)]
-pub fn get_resistor_list(tolerance: f64) -> Vec<Resistor> {
-    let expressions = 0u8..6u8;
-    let e24 = get_e_series_values(Series::E24);
+pub fn get_resistor_list(tolerance: impl Into<Option<f64>>) -> Vec<Resistor> {
+    get_series_resistor_list(Series::E24, tolerance)
+}
+
+pub fn get_series_resistor_list(
+    series: Series,
+    tolerance: impl Into<Option<f64>>,
+) -> Vec<Resistor> {
+    get_resistor_list_for(series, 0..6, tolerance)
+}
+
+// Current-sense shunts, 10 mΩ to 9.1 Ω.
+pub fn get_shunt_resistor_list(series: Series, tolerance: impl Into<Option<f64>>) -> Vec<Resistor> {
+    get_resistor_list_for(series, -2..1, tolerance)
+}
+
+type ValueTables = HashMap<(Series, i32, i32), Arc<[f64]>>;
+
+// The tables are pure functions of (series, decades), so build each once.
+static STANDARD_VALUES: LazyLock<Mutex<ValueTables>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn cached_standard_values(series: Series, decades: Range<i32>) -> Arc<[f64]> {
+    let key = (series, decades.start, decades.end);
+    let mut cache = STANDARD_VALUES.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .entry(key)
+        .or_insert_with(|| {
+            let values = get_e_series_values(series);
+            let mut list: Vec<f64> = decades
+                .flat_map(|exp| values.iter().map(move |v| scale_to_decade(*v, exp)))
+                .collect();
+            list.sort_by(f64::total_cmp);
+            list.dedup();
+            list.into()
+        })
+        .clone()
+}
+
+pub fn standard_resistance_values(series: Series, decades: Range<i32>) -> Vec<f64> {
+    cached_standard_values(series, decades).to_vec()
+}
+
+// A tolerance of None falls back to the series' conventional class.
+pub fn get_resistor_list_for(
+    series: Series,
+    decades: Range<i32>,
+    tolerance: impl Into<Option<f64>>,
+) -> Vec<Resistor> {
+    let tolerance = tolerance
+        .into()
+        .unwrap_or_else(|| series.default_tolerance());
+    cached_standard_values(series, decades)
+        .iter()
+        .map(|&v| Resistor::new(v, tolerance))
+        .collect()
+}
+
+pub fn get_inductor_list(tolerance: f64) -> Vec<Inductor> {
+    let expressions = 0u8..7u8;
+    let e12 = get_e_series_values(Series::E12);
+
+    let value_to_nano = |v: f64, exp: i32| -> f64 { v * 10f64.powi(exp - 9) };
 
     expressions
         .flat_map(|exp| {
-            e24.iter()
-                .map(|v| Resistor::new(*v * 10.0f64.powi(exp as i32), tolerance))
-                .collect::<Vec<Resistor>>()
+            e12.iter()
+                .map(|v| Inductor::new(value_to_nano(*v, exp as i32), tolerance))
+                .collect::<Vec<Inductor>>()
         })
         .collect()
 }
+
+// 1k, 2k and 3k at 1 %, few enough pairs to work out by hand.
+#[cfg(test)]
+pub(crate) fn small_test_resistors() -> Vec<Resistor> {
+    Resistor::from_values(&[1e3, 2e3, 3e3], 0.01)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_tolerances_that_reach_zero() {
+        assert_eq!(Tolerance::try_fraction(1.5), Err(ToleranceError(1.5)));
+        assert_eq!(Tolerance::try_fraction(1.0), Err(ToleranceError(1.0)));
+        assert_eq!(Tolerance::try_fraction(-0.01), Err(ToleranceError(-0.01)));
+        assert!(Resistor::try_new(10e3, 1.5).is_err());
+        assert!(Capacitor::try_new(1e-9, 1.5).is_err());
+        assert_eq!(
+            Resistor::try_with_asymmetric_tolerance(10e3, 0.01, 1.5).err(),
+            Some(ToleranceError(1.5))
+        );
+        let r = Resistor::try_new(10e3, 0.05).unwrap();
+        assert!(r.min() > 0.0 && r.max() > r.get_value());
+    }
+
+    #[test]
+    fn series_and_parallel_combine_the_bounds() {
+        // 9.9k..10.1k with 9.5k..10.5k.
+        let precise = Resistor::new(10e3, 0.01);
+        let loose = Resistor::new(10e3, 0.05);
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        let series = precise.series(&loose);
+        assert!(close(series.get_value(), 20e3));
+        assert!(close(series.min(), 19.4e3));
+        assert!(close(series.max(), 20.6e3));
+
+        // 9.9k ∥ 9.5k and 10.1k ∥ 10.5k.
+        let parallel = precise.parallel(&loose);
+        assert!(close(parallel.get_value(), 5e3));
+        assert!(close(parallel.min(), 9.9e3 * 9.5e3 / 19.4e3));
+        assert!(close(parallel.max(), 10.1e3 * 10.5e3 / 20.6e3));
+    }
+
+    #[test]
+    fn e_series_have_their_nominal_lengths() {
+        assert_eq!(get_e_series_values(Series::E48).len(), 48);
+        assert_eq!(get_e_series_values(Series::E96).len(), 96);
+        let e192 = get_e_series_values(Series::E192);
+        assert_eq!(e192.len(), 192);
+        assert_eq!(e192.first(), Some(&1.00));
+        assert_eq!(e192.last(), Some(&9.88));
+    }
+
+    #[test]
+    fn nearest_e_series_rounds_across_decades_and_ties_down() {
+        let nearest = |value: f64, series: Series| nearest_e_series(value, series).get_value();
+        assert_eq!(nearest(4.8e3, Series::E24), 4.7e3);
+        // 8.2k is 1.4k away, 10k in the next decade only 400 Ω.
+        assert_eq!(nearest(9.6e3, Series::E12), 10e3);
+        assert_eq!(nearest(1.05, Series::E24), 1.0);
+        // 1.25k is exactly between 1k and 1.5k; the smaller one wins.
+        assert_eq!(nearest(1.25e3, Series::E6), 1e3);
+    }
+
+    #[test]
+    fn equality_ignores_rounding_noise() {
+        use std::collections::HashSet;
+
+        // 0.1 + 0.2 style noise, far below the 1 µΩ quantum.
+        let noisy = Resistor::from_values(&[1e3 + 1e-9, 2e3 - 1e-9, 3e3 * (1.0 + 1e-15)], 0.01);
+        assert_eq!(noisy, small_test_resistors());
+        let unique: HashSet<Resistor> = small_test_resistors().into_iter().chain(noisy).collect();
+        assert_eq!(unique.len(), 3);
+
+        assert_ne!(Resistor::new(1e3, 0.01), Resistor::new(1e3, 0.05));
+        assert_ne!(Resistor::new(1e3, 0.01), Resistor::new(1e3 + 1e-3, 0.01));
+    }
+}
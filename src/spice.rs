@@ -0,0 +1,84 @@
+use crate::divider::CircuitParameters;
+use crate::format::spice_value;
+use crate::ranged::Voltage;
+use crate::rc_param::{Network, PassiveComponent, Resistor};
+
+fn element(name: &str, r: &Resistor, a: &str, b: &str) -> String {
+    format!(
+        "{name} {a} {b} {} ; tol {}%",
+        spice_value(r.get_value()),
+        r.get_tolerance() * 100.0
+    )
+}
+
+// One element per physical part, with an internal node for series pairs.
+// Tolerances go into comments since .tol and Monte-Carlo syntax differ
+// between simulators.
+fn arm(name: &str, network: &Network, from: &str, to: &str) -> Vec<String> {
+    match network {
+        Network::Single(r) => vec![element(name, r, from, to)],
+        Network::Series(a, b) => {
+            let mid = format!("{}_mid", name.to_lowercase());
+            vec![
+                element(&format!("{name}a"), a, from, &mid),
+                element(&format!("{name}b"), b, &mid, to),
+            ]
+        }
+        Network::Parallel(a, b) => vec![
+            element(&format!("{name}a"), a, from, to),
+            element(&format!("{name}b"), b, from, to),
+        ],
+    }
+}
+
+pub fn to_spice_netlist(params: &CircuitParameters, v_src: &Voltage) -> String {
+    let mut lines = vec![
+        format!(
+            "* Voltage divider, Vref {:.4} V ({:.4} V to {:.4} V)",
+            params.vref.value,
+            params.vref.min(),
+            params.vref.max()
+        ),
+        format!("V1 in 0 DC {}", v_src.value),
+    ];
+    lines.extend(arm("R1", &params.r1_network, "in", "vref"));
+    lines.extend(arm("R2", &params.r2_network, "vref", "0"));
+    if let Some(load) = &params.load {
+        lines.push(element("RLOAD", load, "vref", "0"));
+    }
+    lines.push(".op".to_string());
+    lines.push(".end".to_string());
+    let mut netlist = lines.join("\n");
+    netlist.push('\n');
+    netlist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::divider::{find_combinations, Constraint};
+
+    #[test]
+    fn writes_the_load_across_r2() {
+        let v_src = Voltage::new_by_allowance(5.0, 0.0);
+        let constraint = Constraint::builder()
+            .voltage(Voltage::new_by_values(1.7, 0.0, 5.0))
+            .load(Resistor::new(10e3, 0.05))
+            .build()
+            .unwrap();
+        let params = find_combinations(constraint, v_src, Resistor::from_values(&[10e3], 0.01))
+            .unwrap()
+            .remove(0);
+        let netlist = to_spice_netlist(&params, &v_src);
+        assert!(netlist.starts_with("* Voltage divider, Vref 1.6667 V"));
+        let elements = netlist
+            .lines()
+            .filter(|l| l.starts_with('R'))
+            .collect::<Vec<_>>();
+        assert_eq!(elements.len(), 3);
+        assert_eq!(
+            elements[2],
+            format!("RLOAD vref 0 {} ; tol 5%", spice_value(10e3))
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::divider::{Constraint, VrefSource};
+use crate::error::SolverError;
+use crate::ranged::Voltage;
+use crate::rc_param::{self, PassiveComponent, Resistor};
+
+#[derive(Debug)]
+pub struct TappedCircuitParameters {
+    pub r1: Resistor,
+    pub r2: Resistor,
+    pub r3: Resistor,
+    pub vrefs: [Voltage; 2],
+    pub vref_errors: [f64; 2],
+}
+
+impl TappedCircuitParameters {
+    fn score(&self) -> (f64, f64) {
+        (
+            self.vref_errors.iter().map(|e| e.powi(2)).sum(),
+            self.r1.get_value() + self.r2.get_value() + self.r3.get_value(),
+        )
+    }
+}
+
+struct RankedTapped(TappedCircuitParameters);
+
+impl PartialEq for RankedTapped {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RankedTapped {}
+
+impl PartialOrd for RankedTapped {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RankedTapped {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (self.0.score(), other.0.score());
+        a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+    }
+}
+
+// Limits shared by both taps: the whole chain carries the same current.
+struct Chain {
+    total_min: f64,
+    total_max: f64,
+    i_max: f64,
+    p_max: f64,
+    p_quiescent: f64,
+}
+
+fn within(constraint: &Constraint, v: f64, r_thevenin_max: f64, chain: &Chain) -> bool {
+    v >= constraint.voltage.min()
+        && v <= constraint.voltage.max()
+        && chain.i_max <= constraint.max_current.value()
+        && chain.total_min >= constraint.min_total_resistance.value()
+        && chain.total_max <= constraint.max_total_resistance.value()
+        && constraint
+            .max_resistor_power
+            .is_none_or(|p_max| chain.p_max <= p_max.value())
+        && constraint
+            .max_thevenin_resistance
+            .is_none_or(|r_max| r_thevenin_max <= r_max.value())
+        && constraint
+            .max_quiescent_power
+            .is_none_or(|p_max| chain.p_quiescent <= p_max.value())
+}
+
+fn parallel(a: f64, b: f64) -> f64 {
+    a * b / (a + b)
+}
+
+// Taps are counted from the supply: constraints[0] is the R1/R2 node and
+// constraints[1] the R2/R3 node. A load would couple the two taps, so
+// constraints with one are rejected rather than silently ignored.
+pub fn find_three_resistor_combinations(
+    constraints: [Constraint; 2],
+    v_src: impl Into<VrefSource>,
+    resistors: Vec<Resistor>,
+    k: usize,
+) -> Result<Vec<TappedCircuitParameters>, SolverError> {
+    let v_src = v_src.into();
+    if resistors.is_empty() {
+        return Err(SolverError::EmptyResistorList);
+    }
+    if !resistors
+        .iter()
+        .all(|r| r.min().is_finite() && r.max().is_finite())
+    {
+        return Err(SolverError::NonFiniteValue);
+    }
+    if constraints.iter().any(|c| c.load.is_some()) {
+        return Err(SolverError::UnsupportedLoad);
+    }
+    if k == 0 {
+        return Ok(Vec::new());
+    }
+
+    let resistors = rc_param::without_zero_values(resistors);
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for r1 in &resistors {
+        for r2 in &resistors {
+            for r3 in &resistors {
+                let total = r1.get_value() + r2.get_value() + r3.get_value();
+                let total_min = r1.min() + r2.min() + r3.min();
+                let total_max = r1.max() + r2.max() + r3.max();
+                let i_max = v_src.max() / total_min;
+                let parts = [r1, r2, r3];
+                if parts
+                    .iter()
+                    .any(|r| i_max.powi(2) * r.max() > r.get_power_rating())
+                {
+                    continue;
+                }
+                let chain = Chain {
+                    total_min,
+                    total_max,
+                    i_max,
+                    p_max: parts
+                        .iter()
+                        .map(|r| i_max.powi(2) * r.max())
+                        .fold(0.0, f64::max),
+                    p_quiescent: v_src.max() * i_max,
+                };
+                let upper = (r2.get_value() + r3.get_value()) / total * v_src.value();
+                let lower = r3.get_value() / total * v_src.value();
+                let upper_thevenin = parallel(r1.max(), r2.max() + r3.max());
+                let lower_thevenin = parallel(r1.max() + r2.max(), r3.max());
+                if !(within(&constraints[0], upper, upper_thevenin, &chain)
+                    && within(&constraints[1], lower, lower_thevenin, &chain))
+                {
+                    continue;
+                }
+                let upper = Voltage::new_by_values(
+                    upper,
+                    (r2.min() + r3.min()) / (r1.max() + r2.min() + r3.min()) * v_src.min(),
+                    (r2.max() + r3.max()) / (r1.min() + r2.max() + r3.max()) * v_src.max(),
+                );
+                let lower = Voltage::new_by_values(
+                    lower,
+                    r3.min() / (r1.max() + r2.max() + r3.min()) * v_src.min(),
+                    r3.max() / (r1.min() + r2.min() + r3.max()) * v_src.max(),
+                );
+                heap.push(RankedTapped(TappedCircuitParameters {
+                    r1: *r1,
+                    r2: *r2,
+                    r3: *r3,
+                    vrefs: [upper, lower],
+                    vref_errors: [
+                        upper.value - constraints[0].voltage.value,
+                        lower.value - constraints[1].voltage.value,
+                    ],
+                }));
+                if heap.len() > k {
+                    heap.pop();
+                }
+            }
+        }
+    }
+    if heap.is_empty() {
+        return Err(SolverError::NoCombinationFound);
+    }
+    Ok(heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|ranked| ranked.0)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rc_param::{get_series_resistor_list, Series};
+    use crate::units::{Ohms, Watts};
+
+    fn taps() -> [Constraint; 2] {
+        let tap = |v: f64| {
+            Constraint::builder()
+                .voltage(Voltage::new_by_values(v, v * 0.9, v * 1.1))
+                .max_total_resistance(Ohms(200e3))
+        };
+        [tap(3.3).build().unwrap(), tap(1.2).build().unwrap()]
+    }
+
+    fn values(results: &[TappedCircuitParameters]) -> Vec<[f64; 3]> {
+        results
+            .iter()
+            .map(|p| [p.r1.get_value(), p.r2.get_value(), p.r3.get_value()])
+            .collect()
+    }
+
+    #[test]
+    fn top_k_matches_the_head_of_the_full_ranking() {
+        let v_src = Voltage::new_by_allowance(5.0, 0.05);
+        let resistors = get_series_resistor_list(Series::E12, 0.01);
+        let all =
+            find_three_resistor_combinations(taps(), v_src, resistors.clone(), 100_000).unwrap();
+        let top = find_three_resistor_combinations(taps(), v_src, resistors, 3).unwrap();
+        assert!(all.len() > 3);
+        assert_eq!(values(&top), values(&all[..3]));
+    }
+
+    #[test]
+    fn applies_power_and_thevenin_limits() {
+        let v_src = Voltage::new_by_allowance(5.0, 0.05);
+        let resistors = get_series_resistor_list(Series::E12, 0.01);
+        let p_quiescent = |p: &TappedCircuitParameters| {
+            v_src.max().powi(2) / (p.r1.min() + p.r2.min() + p.r3.min())
+        };
+        let r_thevenin =
+            |p: &TappedCircuitParameters| parallel(p.r1.max() + p.r2.max(), p.r3.max());
+        let within_limits = |p: &_| p_quiescent(p) <= 2e-3 && r_thevenin(p) <= 5e3;
+
+        let unlimited =
+            find_three_resistor_combinations(taps(), v_src, resistors.clone(), 100_000).unwrap();
+        assert!(!unlimited.iter().all(within_limits));
+
+        let [upper, mut lower] = taps();
+        lower.max_thevenin_resistance = Some(Ohms(5e3));
+        lower.max_quiescent_power = Some(Watts(2e-3));
+        let limited =
+            find_three_resistor_combinations([upper, lower], v_src, resistors, 100_000).unwrap();
+        assert!(limited.iter().all(within_limits));
+    }
+
+    #[test]
+    fn rejects_a_load() {
+        let [upper, mut lower] = taps();
+        lower.load = Some(Resistor::new(10e3, 0.01));
+        let result = find_three_resistor_combinations(
+            [upper, lower],
+            Voltage::new_by_allowance(5.0, 0.0),
+            get_series_resistor_list(Series::E12, 0.01),
+            3,
+        );
+        assert_eq!(result.err(), Some(SolverError::UnsupportedLoad));
+    }
+
+    #[test]
+    fn ignores_zero_ohm_parts() {
+        let v_src = Voltage::new_by_allowance(5.0, 0.05);
+        let mut resistors = get_series_resistor_list(Series::E12, 0.01);
+        let without =
+            find_three_resistor_combinations(taps(), v_src, resistors.clone(), 5).unwrap();
+        resistors.push(Resistor::new(0.0, 0.01));
+        let with_zero = find_three_resistor_combinations(taps(), v_src, resistors, 5).unwrap();
+        assert_eq!(values(&with_zero), values(&without));
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt;
+use std::ops::{Div, Mul};
+
+// Thin wrappers so a resistance cannot be passed where a current is
+// expected. There is no From<f64>, so a bare number has to be wrapped in
+// the unit it is meant to be.
+macro_rules! unit {
+    ($name:ident, $symbol:expr) => {
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "serde", serde(transparent))]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+        pub struct $name(pub f64);
+
+        impl $name {
+            pub fn value(self) -> f64 {
+                self.0
+            }
+        }
+
+        impl From<$name> for f64 {
+            fn from(value: $name) -> f64 {
+                value.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    "{}",
+                    crate::format::engineering_notation(self.0, $symbol)
+                )
+            }
+        }
+    };
+}
+
+unit!(Ohms, "Ω");
+unit!(Volts, "V");
+unit!(Amps, "A");
+unit!(Watts, "W");
+
+impl Div<Ohms> for Volts {
+    type Output = Amps;
+    fn div(self, rhs: Ohms) -> Amps {
+        Amps(self.0 / rhs.0)
+    }
+}
+
+impl Div<Amps> for Volts {
+    type Output = Ohms;
+    fn div(self, rhs: Amps) -> Ohms {
+        Ohms(self.0 / rhs.0)
+    }
+}
+
+impl Mul<Ohms> for Amps {
+    type Output = Volts;
+    fn mul(self, rhs: Ohms) -> Volts {
+        Volts(self.0 * rhs.0)
+    }
+}